@@ -0,0 +1,83 @@
+use dbus::Connection;
+
+use network_manager::OrgFreedesktopNetworkManager;
+use network_manager::OrgFreedesktopNetworkManagerConnectionActive;
+
+// NMActiveConnectionState
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMActiveConnectionState
+pub const NM_ACTIVE_CONNECTION_STATE_ACTIVATING: u32 = 1;
+pub const NM_ACTIVE_CONNECTION_STATE_ACTIVATED: u32 = 2;
+
+// Connection types that tunnel traffic through another connection
+const VPN_TYPES: [&'static str; 3] = ["vpn", "wireguard", "tun"];
+
+pub fn is_vpn_type(connection_type: &str) -> bool {
+    VPN_TYPES.contains(&connection_type)
+}
+
+// One of NetworkManager's active connections
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActiveConnection {
+    pub path: String,
+    pub name: String,
+    pub uuid: String,
+    pub connection_type: String,
+    pub state: u32,
+    pub vpn: bool,
+    pub devices: Vec<String>,
+    // Active connection a VPN goes through, NetworkManager's VPN plugins
+    // set it as the specific object
+    pub parent: Option<String>
+}
+
+impl ActiveConnection {
+    pub fn load(conn: &Connection, path: &str) -> Option<ActiveConnection> {
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", path, 5000);
+        // The connection may already be gone
+        let connection_type = match c_path.get_type() {
+            Ok(connection_type) => connection_type,
+            Err(_) => return None
+        };
+        let vpn = c_path.get_vpn().unwrap_or(false) || is_vpn_type(&connection_type);
+        let parent = if vpn {
+            match c_path.get_specific_object() {
+                Ok(ref parent) if &**parent != "/" => Some(parent.to_string()),
+                _ => None
+            }
+        } else {
+            None
+        };
+        Some(ActiveConnection {
+            path: String::from(path),
+            name: c_path.get_id().unwrap_or(String::new()),
+            uuid: c_path.get_uuid().unwrap_or(String::new()),
+            state: OrgFreedesktopNetworkManagerConnectionActive::get_state(&c_path).unwrap_or(0),
+            vpn: vpn,
+            devices: OrgFreedesktopNetworkManagerConnectionActive::get_devices(&c_path)
+                .map(|devices| devices.iter().map(|device| device.to_string()).collect())
+                .unwrap_or(Vec::new()),
+            parent: parent,
+            connection_type: connection_type
+        })
+    }
+
+    pub fn is_activated(&self) -> bool {
+        self.state == NM_ACTIVE_CONNECTION_STATE_ACTIVATED
+    }
+}
+
+pub fn load_active_connections(conn: &Connection) -> Vec<ActiveConnection> {
+    let c_path = conn.with_path(
+        "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+    match c_path.get_active_connections() {
+        Ok(paths) => {
+            paths.iter()
+                .filter_map(|path| ActiveConnection::load(conn, path))
+                .collect()
+        },
+        Err(err) => {
+            error!("Could not get active connections: {:?}", err);
+            Vec::new()
+        }
+    }
+}
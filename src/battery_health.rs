@@ -0,0 +1,151 @@
+use dbus::Connection;
+use dbus::BusType;
+
+use config::Config;
+use upower::OrgFreedesktopUPower;
+use upower::OrgFreedesktopUPowerDevice;
+
+// Type constants
+// https://upower.freedesktop.org/docs/Device.html#Device:Type
+const TYPE_BATTERY: u32 = 2;
+
+// Capacity below which battery health is reported as low by default
+const DEFAULT_HEALTH_WARNING_PERCENTAGE: f64 = 70.0;
+
+pub fn get_warning_percentage(config: &Config) -> f64 {
+    config.get_i64("power", "health_warning_percentage")
+        .map(|percentage| percentage as f64)
+        .unwrap_or(DEFAULT_HEALTH_WARNING_PERCENTAGE)
+}
+
+// Health of one of the system's batteries. Values UPower does not know
+// about are None.
+#[derive(Clone, Debug)]
+pub struct BatteryHealth {
+    pub model: String,
+    pub capacity: f64,
+    pub energy_full: f64,
+    pub energy_full_design: f64,
+    pub technology: u32,
+    pub temperature: Option<f64>,
+    pub voltage: Option<f64>,
+    pub charge_cycles: Option<i32>
+}
+
+impl BatteryHealth {
+    pub fn is_low(&self, warning_percentage: f64) -> bool {
+        self.capacity > 0.0 && self.capacity < warning_percentage
+    }
+
+    // One line summary for the battery tooltip
+    pub fn summary(&self) -> String {
+        format!("Health: {:.0}% ({:.1} / {:.1} Wh)",
+            self.capacity, self.energy_full, self.energy_full_design)
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines = vec![
+            format!("Battery:        {}", if self.model.is_empty() { "unknown" } else { &self.model }),
+            format!("Capacity:       {:.1}%", self.capacity),
+            format!("Energy full:    {:.1} Wh", self.energy_full),
+            format!("Design energy:  {:.1} Wh", self.energy_full_design),
+            format!("Technology:     {}", get_technology_description(self.technology))
+        ];
+        if let Some(temperature) = self.temperature {
+            lines.push(format!("Temperature:    {:.1} °C", temperature));
+        }
+        if let Some(voltage) = self.voltage {
+            lines.push(format!("Voltage:        {:.2} V", voltage));
+        }
+        if let Some(charge_cycles) = self.charge_cycles {
+            lines.push(format!("Charge cycles:  {}", charge_cycles));
+        }
+        lines.join("\n")
+    }
+}
+
+fn get_technology_description(technology: u32) -> &'static str {
+    match technology {
+        1 => "lithium ion",
+        2 => "lithium polymer",
+        3 => "lithium iron phosphate",
+        4 => "lead acid",
+        5 => "nickel cadmium",
+        6 => "nickel metal hydride",
+        _ => "unknown"
+    }
+}
+
+// Whether the device is one of the laptop's own batteries, not a UPS or a
+// peripheral
+fn is_system_battery(conn: &Connection, path: &str) -> bool {
+    let c_path = conn.with_path("org.freedesktop.UPower", path, 5000);
+    match (c_path.get_type(), c_path.get_power_supply(), c_path.get_is_present()) {
+        (Ok(TYPE_BATTERY), Ok(true), Ok(true)) => true,
+        _ => false
+    }
+}
+
+pub fn get_battery_paths(conn: &Connection) -> Vec<String> {
+    let c_path = conn.with_path(
+        "org.freedesktop.UPower", "/org/freedesktop/UPower", 5000);
+    match c_path.enumerate_devices() {
+        Ok(devices) => {
+            devices.iter()
+                .map(|path| path.to_string())
+                .filter(|path| is_system_battery(conn, path))
+                .collect()
+        },
+        Err(err) => {
+            error!("Could not enumerate UPower devices: {:?}", err);
+            Vec::new()
+        }
+    }
+}
+
+fn get_device_health(conn: &Connection, path: &str) -> BatteryHealth {
+    let c_path = conn.with_path("org.freedesktop.UPower", path, 5000);
+    BatteryHealth {
+        model: c_path.get_model().unwrap_or(String::new()),
+        capacity: c_path.get_capacity().unwrap_or(0.0),
+        energy_full: c_path.get_energy_full().unwrap_or(0.0),
+        energy_full_design: c_path.get_energy_full_design().unwrap_or(0.0),
+        technology: c_path.get_technology().unwrap_or(0),
+        temperature: c_path.get_temperature().ok().and_then(|t| if t != 0.0 { Some(t) } else { None }),
+        voltage: c_path.get_voltage().ok().and_then(|v| if v != 0.0 { Some(v) } else { None }),
+        // Older UPower versions don't have ChargeCycles, -1 means unknown
+        charge_cycles: c_path.get_charge_cycles().ok().and_then(|c| if c > 0 { Some(c) } else { None })
+    }
+}
+
+pub fn get_battery_health(conn: &Connection) -> Vec<BatteryHealth> {
+    get_battery_paths(conn).iter()
+        .map(|path| get_device_health(conn, path))
+        .collect()
+}
+
+// Prints the `sgstatus battery --health` report, returns the exit code
+pub fn print_health_report(warning_percentage: f64) -> i32 {
+    let conn = match Connection::get_private(BusType::System) {
+        Ok(c) => c,
+        Err(err) => {
+            eprintln!("Could not connect to the system bus: {:?}", err);
+            return 1
+        }
+    };
+    let batteries = get_battery_health(&conn);
+    if batteries.is_empty() {
+        println!("No battery found");
+        return 1
+    }
+    let reports: Vec<String> = batteries.iter().map(|battery| {
+        let mut report = battery.report();
+        if battery.is_low(warning_percentage) {
+            report = format!("{}\nWarning: capacity is below {:.0}% of the design capacity",
+                report, warning_percentage);
+        }
+        report
+    }).collect();
+    println!("{}", reports.join("\n\n"));
+    return 0
+}
@@ -0,0 +1,98 @@
+use dbus::Connection;
+
+use config::Config;
+use upower::OrgFreedesktopUPowerDevice;
+
+const SPARK_CHARACTERS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const DEFAULT_HISTORY_HOURS: i64 = 6;
+const DEFAULT_HISTORY_WIDTH: i64 = 12;
+
+// Where the history sparkline is shown
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HistoryOutput {
+    Text,
+    Tooltip
+}
+
+// Charge or rate history of a battery from UPower's GetHistory, rendered as
+// a Unicode sparkline
+pub struct BatteryHistory {
+    pub output: HistoryOutput,
+    history_type: String,
+    hours: u32,
+    width: u32
+}
+
+impl BatteryHistory {
+    pub fn new(config: &Config) -> Option<BatteryHistory> {
+        let output = match config.get_str("power", "history") {
+            Some("text") => HistoryOutput::Text,
+            Some("tooltip") => HistoryOutput::Tooltip,
+            Some("off") | None => return None,
+            Some(output) => {
+                error!("Unknown battery history output: {}", output);
+                return None
+            }
+        };
+        let history_type = match config.get_str("power", "history_type") {
+            Some("rate") => "rate",
+            Some("charge") | None => "charge",
+            Some(history_type) => {
+                error!("Unknown battery history type: {}", history_type);
+                "charge"
+            }
+        };
+        let hours = config.get_i64("power", "history_hours")
+            .unwrap_or(DEFAULT_HISTORY_HOURS);
+        let width = config.get_i64("power", "history_width")
+            .unwrap_or(DEFAULT_HISTORY_WIDTH);
+        Some(BatteryHistory {
+            output: output,
+            history_type: String::from(history_type),
+            hours: hours.max(1) as u32,
+            width: width.max(1) as u32
+        })
+    }
+
+    pub fn description(&self) -> String {
+        format!("{} over the last {}h", self.history_type, self.hours)
+    }
+
+    // Fetches the history of the battery at `path` and renders it, None when
+    // UPower has no history for it yet
+    pub fn get_sparkline(&self, conn: &Connection, path: &str) -> Option<String> {
+        let c_path = conn.with_path("org.freedesktop.UPower", path, 5000);
+        let mut history = match c_path.get_history(
+            &self.history_type, self.hours * 3600, self.width) {
+            Ok(history) => history,
+            Err(err) => {
+                error!("Could not get battery history: {:?}", err);
+                return None
+            }
+        };
+        if history.is_empty() {
+            return None
+        }
+        history.sort_by_key(|&(time, _, _)| time);
+        let values: Vec<f64> = history.iter().map(|&(_, value, _)| value).collect();
+        let max = match self.history_type.as_ref() {
+            "charge" => 100.0,
+            _ => values.iter().cloned().fold(0.0, f64::max)
+        };
+        Some(sparkline(&values, max))
+    }
+}
+
+// Renders values between 0 and `max` as a line of block characters
+pub fn sparkline(values: &[f64], max: f64) -> String {
+    let top = (SPARK_CHARACTERS.len() - 1) as f64;
+    values.iter().map(|value| {
+        let level = if max > 0.0 {
+            (value.max(0.0).min(max) / max * top).round() as usize
+        } else {
+            0
+        };
+        SPARK_CHARACTERS[level]
+    }).collect()
+}
@@ -0,0 +1,259 @@
+use dbus::Connection;
+use dbus::BusType;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+
+use battery_health;
+use config::Config;
+use status::{Action, ActionHandler};
+use upower::OrgFreedesktopUPowerDevice;
+
+// Batteries are exposed by the kernel under this directory, UPower's
+// NativePath is usually the name of the battery's node in it
+const POWER_SUPPLY_PATH: &'static str = "/sys/class/power_supply";
+
+// Presets the battery item switches between when clicked
+const DEFAULT_PRESETS: &'static str = "60-80, 0-100";
+
+// Kernel charge control thresholds, laptops with thresholds set stop charging
+// at the end threshold and only start again below the start threshold
+// https://www.kernel.org/doc/Documentation/ABI/testing/sysfs-class-power
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChargeThresholds {
+    pub start: Option<u32>,
+    pub end: Option<u32>
+}
+
+fn read_threshold(path: &PathBuf) -> Option<u32> {
+    fs::read_to_string(path).ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+impl ChargeThresholds {
+    pub fn read(sysfs_path: &PathBuf) -> ChargeThresholds {
+        ChargeThresholds {
+            start: read_threshold(&sysfs_path.join("charge_control_start_threshold")),
+            end: read_threshold(&sysfs_path.join("charge_control_end_threshold"))
+        }
+    }
+
+    fn new(start: u32, end: u32) -> ChargeThresholds {
+        ChargeThresholds {
+            start: Some(start),
+            end: Some(end)
+        }
+    }
+
+    // Parses "60-80"
+    fn parse(preset: &str) -> Option<ChargeThresholds> {
+        let mut bounds = preset.trim().splitn(2, '-')
+            .map(|bound| bound.trim().parse::<u32>());
+        match (bounds.next(), bounds.next()) {
+            (Some(Ok(start)), Some(Ok(end))) if start < end && end <= 100 => {
+                Some(ChargeThresholds::new(start, end))
+            },
+            _ => None
+        }
+    }
+
+    // Whether charging stops before the battery is full
+    pub fn is_limited(&self) -> bool {
+        match self.end {
+            Some(end) => end < 100,
+            None => false
+        }
+    }
+
+    // Tooltip line, None when the battery charges to 100%
+    pub fn description(&self) -> Option<String> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if self.is_limited() && start > 0 => {
+                Some(format!("Charge thresholds: {}–{}%", start, end))
+            },
+            (_, Some(end)) if self.is_limited() => {
+                Some(format!("Charging stops at {}%", end))
+            },
+            _ => None
+        }
+    }
+
+    fn preset_name(&self) -> String {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if self.is_limited() => {
+                format!("conservation {}–{}%", start, end)
+            },
+            _ => String::from("full charge")
+        }
+    }
+}
+
+// Sysfs node of the UPower battery at `path`
+pub fn get_sysfs_path(conn: &Connection, path: &str) -> Option<PathBuf> {
+    let c_path = conn.with_path("org.freedesktop.UPower", path, 5000);
+    match c_path.get_native_path() {
+        Ok(ref native_path) if native_path.is_empty() => None,
+        // Some UPower versions report the full sysfs path
+        Ok(ref native_path) if native_path.starts_with('/') => Some(PathBuf::from(native_path)),
+        Ok(native_path) => Some(PathBuf::from(POWER_SUPPLY_PATH).join(native_path)),
+        Err(err) => {
+            error!("Could not get battery native path: {:?}", err);
+            None
+        }
+    }
+}
+
+// Sysfs nodes of the system's batteries that support charge thresholds
+pub fn get_threshold_paths(conn: &Connection) -> Vec<PathBuf> {
+    battery_health::get_battery_paths(conn).iter()
+        .filter_map(|path| get_sysfs_path(conn, path))
+        .filter(|path| path.join("charge_control_end_threshold").exists())
+        .collect()
+}
+
+fn write_threshold(path: &PathBuf, name: &str, value: u32) -> io::Result<()> {
+    fs::write(path.join(name), format!("{}\n", value))
+}
+
+// The kernel refuses a start threshold above the current end threshold and
+// the other way around, so the order depends on the direction of the change
+fn write_thresholds(path: &PathBuf, thresholds: ChargeThresholds) -> io::Result<()> {
+    let current = ChargeThresholds::read(path);
+    let raises_end = match (current.end, thresholds.end) {
+        (Some(current_end), Some(end)) => end > current_end,
+        _ => true
+    };
+    let mut writes = vec![
+        ("charge_control_start_threshold", thresholds.start),
+        ("charge_control_end_threshold", thresholds.end)
+    ];
+    if raises_end {
+        writes.reverse();
+    }
+    for (name, value) in writes {
+        if let Some(value) = value {
+            // Some laptops only support the end threshold
+            if name == "charge_control_start_threshold" && !path.join(name).exists() {
+                continue
+            }
+            try!(write_threshold(path, name, value));
+        }
+    }
+    Ok(())
+}
+
+// Runs `sgstatus battery --set-thresholds START END`, the helper started
+// through pkexec by the battery item. Returns the exit code.
+pub fn run_set_thresholds(start: &str, end: &str) -> i32 {
+    let thresholds = match ChargeThresholds::parse(&format!("{}-{}", start, end)) {
+        Some(thresholds) => thresholds,
+        None => {
+            eprintln!("Invalid charge thresholds: {} {}", start, end);
+            return 2
+        }
+    };
+    let conn = match Connection::get_private(BusType::System) {
+        Ok(c) => c,
+        Err(err) => {
+            eprintln!("Could not connect to the system bus: {:?}", err);
+            return 1
+        }
+    };
+    let paths = get_threshold_paths(&conn);
+    if paths.is_empty() {
+        eprintln!("No battery supports charge thresholds");
+        return 1
+    }
+    for path in paths.iter() {
+        if let Err(err) = write_thresholds(path, thresholds) {
+            eprintln!("Could not set charge thresholds of {}: {}", path.display(), err);
+            return 1
+        }
+    }
+    return 0
+}
+
+pub fn get_presets(config: &Config) -> Vec<ChargeThresholds> {
+    let presets = config.get_str("power", "charge_thresholds")
+        .unwrap_or(DEFAULT_PRESETS);
+    presets.split(',')
+        .filter_map(|preset| {
+            let thresholds = ChargeThresholds::parse(preset);
+            if thresholds.is_none() {
+                error!("Invalid charge threshold preset: {}", preset);
+            }
+            thresholds
+        })
+        .collect()
+}
+
+// Writing to sysfs needs root, so the thresholds are set by running this
+// executable again through pkexec, which asks polkit for authorization
+fn apply_preset(preset: ChargeThresholds) {
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => {
+            error!("Could not find the sgstatus executable: {:?}", err);
+            return
+        }
+    };
+    info!("Switching charge thresholds to {}", preset.preset_name());
+    let child = Command::new("pkexec")
+        .arg(exe)
+        .args(&["battery", "--set-thresholds"])
+        .arg(preset.start.unwrap_or(0).to_string())
+        .arg(preset.end.unwrap_or(100).to_string())
+        .spawn();
+    match child {
+        // The authorization dialog can stay open for a while, don't block
+        // the item on it
+        Ok(mut child) => {
+            thread::spawn(move || {
+                match child.wait() {
+                    Ok(status) if status.success() => info!("Charge thresholds set"),
+                    Ok(status) => error!("Could not set charge thresholds: {}", status),
+                    Err(err) => error!("Could not set charge thresholds: {:?}", err)
+                }
+            });
+        },
+        Err(err) => error!("Could not run pkexec: {:?}", err)
+    }
+}
+
+// Clicking the battery item switches to the next preset, None when no
+// battery supports charge thresholds
+pub fn get_action_handler(config: &Config) -> Option<ActionHandler> {
+    let conn = match Connection::get_private(BusType::System) {
+        Ok(c) => c,
+        Err(err) => {
+            error!("Could not connect to the system bus: {:?}", err);
+            return None
+        }
+    };
+    let presets = get_presets(config);
+    if presets.len() < 2 || get_threshold_paths(&conn).is_empty() {
+        return None
+    }
+    Some(Box::new(move |action| {
+        if action != Action::Activate {
+            return
+        }
+        let current = match get_threshold_paths(&conn).first() {
+            Some(path) => ChargeThresholds::read(path),
+            None => return
+        };
+        // Batteries that only support the end threshold match on it alone
+        let index = presets.iter()
+            .position(|preset| *preset == current || 
+                (current.start.is_none() && preset.end == current.end));
+        let next = match index {
+            Some(index) => presets[(index + 1) % presets.len()],
+            None => presets[0]
+        };
+        apply_preset(next);
+    }))
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+// sgstatus configuration, read from `$XDG_CONFIG_HOME/sgstatus/config`.
+//
+// The file is made of `[section]` headers followed by `key = value` lines,
+// lines starting with `#` are comments. Every option has a default, so a
+// missing file or key is not an error.
+#[derive(Clone, Default, Debug)]
+pub struct Config {
+    sections: HashMap<String, HashMap<String, String>>
+}
+
+impl Config {
+    pub fn load() -> Config {
+        let path = match config_path() {
+            Some(path) => path,
+            None => {
+                info!("No configuration directory found, using defaults");
+                return Config::default()
+            }
+        };
+        let mut contents = String::new();
+        match File::open(&path).and_then(|mut f| f.read_to_string(&mut contents)) {
+            Ok(_) => {
+                info!("Loaded configuration from {:?}", path);
+                Config::parse(&contents)
+            },
+            Err(err) => {
+                info!("Could not read configuration {:?}, using defaults: {}",
+                    path, err);
+                Config::default()
+            }
+        }
+    }
+
+    pub fn parse(contents: &str) -> Config {
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut section = String::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..line.len() - 1].trim().to_string();
+                continue
+            }
+            match line.find('=') {
+                Some(index) => {
+                    let key = line[..index].trim().to_string();
+                    let value = line[index + 1..].trim().to_string();
+                    sections.entry(section.clone())
+                        .or_insert_with(HashMap::new)
+                        .insert(key, value);
+                },
+                None => error!("Invalid configuration line {}: {}", number + 1, line)
+            }
+        }
+        Config {
+            sections: sections
+        }
+    }
+
+    pub fn get_str(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections.get(section)
+            .and_then(|keys| keys.get(key))
+            .map(|value| value.as_str())
+    }
+
+    pub fn get_i64(&self, section: &str, key: &str) -> Option<i64> {
+        match self.get_str(section, key) {
+            Some(value) => match value.parse::<i64>() {
+                Ok(number) => Some(number),
+                Err(_) => {
+                    error!("Invalid number for {}.{}: {}", section, key, value);
+                    None
+                }
+            },
+            None => None
+        }
+    }
+
+    pub fn get_bool(&self, section: &str, key: &str) -> Option<bool> {
+        match self.get_str(section, key) {
+            Some("true") | Some("yes") | Some("on") => Some(true),
+            Some("false") | Some("no") | Some("off") => Some(false),
+            Some(value) => {
+                error!("Invalid boolean for {}.{}: {}", section, key, value);
+                None
+            },
+            None => None
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let mut path = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".config"),
+            None => return None
+        }
+    };
+    path.push("sgstatus");
+    path.push("config");
+    Some(path)
+}
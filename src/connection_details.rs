@@ -0,0 +1,158 @@
+use dbus::Connection;
+use dbus::arg::{RefArg, Variant};
+
+use std::collections::HashMap;
+use std::net::Ipv6Addr;
+
+use menu::MenuItem;
+use network_manager::OrgFreedesktopNetworkManagerAccessPoint;
+use network_manager::OrgFreedesktopNetworkManagerConnectionActive;
+use network_manager::OrgFreedesktopNetworkManagerDevice;
+use network_manager::OrgFreedesktopNetworkManagerDeviceWireless;
+use network_manager::OrgFreedesktopNetworkManagerIP4Config;
+use network_manager::OrgFreedesktopNetworkManagerIP6Config;
+
+// What the network item tells about the primary connection
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionDetails {
+    // SSID for Wi-Fi, the connection's name otherwise
+    pub name: String,
+    pub interface: String,
+    pub ipv4_addresses: Vec<String>,
+    pub ipv4_gateway: String,
+    pub ipv6_addresses: Vec<String>,
+    pub ipv6_gateway: String,
+    pub dns_servers: Vec<String>
+}
+
+fn is_valid_path(path: &str) -> bool {
+    !path.is_empty() && path != "/"
+}
+
+// Formats AddressData entries as "192.168.1.2/24"
+fn get_addresses(address_data: &[HashMap<String, Variant<Box<RefArg>>>]) -> Vec<String> {
+    address_data.iter().filter_map(|address| {
+        let prefix = address.get("prefix").and_then(|prefix| prefix.0.as_i64());
+        address.get("address")
+            .and_then(|address| address.0.as_str())
+            .map(|address| match prefix {
+                Some(prefix) => format!("{}/{}", address, prefix),
+                None => String::from(address)
+            })
+    }).collect()
+}
+
+fn get_ipv6_address(bytes: &[u8]) -> Option<String> {
+    if bytes.len() != 16 {
+        return None
+    }
+    let mut octets = [0u8; 16];
+    octets.copy_from_slice(bytes);
+    Some(Ipv6Addr::from(octets).to_string())
+}
+
+fn get_ssid(conn: &Connection, device: &str) -> Option<String> {
+    let c_path = conn.with_path("org.freedesktop.NetworkManager", device, 5000);
+    let access_point = match c_path.get_active_access_point() {
+        Ok(ref access_point) if is_valid_path(access_point) => access_point.to_string(),
+        _ => return None
+    };
+    let c_path = conn.with_path("org.freedesktop.NetworkManager", access_point, 5000);
+    match c_path.get_ssid() {
+        Ok(ref ssid) if !ssid.is_empty() => Some(String::from_utf8_lossy(ssid).into_owned()),
+        _ => None
+    }
+}
+
+impl ConnectionDetails {
+    // Details of the active connection at `connection`, going through its
+    // first device for the interface name
+    pub fn load(conn: &Connection, connection: &str) -> ConnectionDetails {
+        let mut details = ConnectionDetails::default();
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", connection, 5000);
+        details.name = c_path.get_id().unwrap_or(String::new());
+        if let Some(device) = c_path.get_devices().ok().and_then(|devices| devices.first().cloned()) {
+            let d_path = conn.with_path("org.freedesktop.NetworkManager", device.clone(), 5000);
+            details.interface = d_path.get_ip_interface()
+                .or_else(|_| d_path.get_interface())
+                .unwrap_or(String::new());
+            if let Some(ssid) = get_ssid(conn, &device) {
+                details.name = ssid;
+            }
+        }
+        match OrgFreedesktopNetworkManagerConnectionActive::get_ip4_config(&c_path) {
+            Ok(ref config) if is_valid_path(config) => details.load_ipv4(conn, &config),
+            _ => {}
+        }
+        match OrgFreedesktopNetworkManagerConnectionActive::get_ip6_config(&c_path) {
+            Ok(ref config) if is_valid_path(config) => details.load_ipv6(conn, &config),
+            _ => {}
+        }
+        details
+    }
+
+    fn load_ipv4(&mut self, conn: &Connection, config: &str) {
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", config, 5000);
+        if let Ok(address_data) = OrgFreedesktopNetworkManagerIP4Config::get_address_data(&c_path) {
+            self.ipv4_addresses = get_addresses(&address_data);
+        }
+        self.ipv4_gateway = OrgFreedesktopNetworkManagerIP4Config::get_gateway(&c_path)
+            .unwrap_or(String::new());
+        if let Ok(nameservers) = c_path.get_nameserver_data() {
+            self.dns_servers.extend(nameservers.iter()
+                .filter_map(|nameserver| nameserver.get("address"))
+                .filter_map(|address| address.0.as_str().map(String::from)));
+        }
+    }
+
+    fn load_ipv6(&mut self, conn: &Connection, config: &str) {
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", config, 5000);
+        if let Ok(address_data) = OrgFreedesktopNetworkManagerIP6Config::get_address_data(&c_path) {
+            self.ipv6_addresses = get_addresses(&address_data);
+        }
+        self.ipv6_gateway = OrgFreedesktopNetworkManagerIP6Config::get_gateway(&c_path)
+            .unwrap_or(String::new());
+        if let Ok(nameservers) = c_path.get_nameservers() {
+            self.dns_servers.extend(nameservers.iter()
+                .filter_map(|nameserver| get_ipv6_address(nameserver)));
+        }
+    }
+
+    // "Label: value" lines, shared by the tooltip and the menu
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.name.is_empty() {
+            lines.push(format!("Connection: {}", self.name));
+        }
+        if !self.interface.is_empty() {
+            lines.push(format!("Interface: {}", self.interface));
+        }
+        for address in self.ipv4_addresses.iter() {
+            lines.push(format!("IPv4: {}", address));
+        }
+        if !self.ipv4_gateway.is_empty() {
+            lines.push(format!("Gateway: {}", self.ipv4_gateway));
+        }
+        for address in self.ipv6_addresses.iter() {
+            lines.push(format!("IPv6: {}", address));
+        }
+        if !self.ipv6_gateway.is_empty() {
+            lines.push(format!("IPv6 gateway: {}", self.ipv6_gateway));
+        }
+        if !self.dns_servers.is_empty() {
+            lines.push(format!("DNS: {}", self.dns_servers.join(", ")));
+        }
+        lines
+    }
+
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        self.lines().into_iter().map(MenuItem::new).collect()
+    }
+
+    // Address shown in the status text, without the prefix length
+    pub fn get_address(&self) -> Option<String> {
+        self.ipv4_addresses.first()
+            .or(self.ipv6_addresses.first())
+            .map(|address| address.split('/').next().unwrap_or("").to_string())
+    }
+}
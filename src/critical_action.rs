@@ -0,0 +1,262 @@
+use dbus::Connection;
+use dbus::BusType;
+use dbus::SignalArgs;
+
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use config::Config;
+use logind::OrgFreedesktopLogin1Manager;
+use notifications::OrgFreedesktopNotifications;
+use notifications::OrgFreedesktopNotificationsActionInvoked;
+use notify;
+use notify::URGENCY_CRITICAL;
+use power::{CHARGING, DISCHARGING, EMPTY, FULLY_CHARGED, PENDING_CHARGE};
+
+const DEFAULT_ACTION_PERCENTAGE: i64 = 3;
+const DEFAULT_ACTION_DELAY: u64 = 60;
+
+// How often the countdown notification is refreshed, in seconds
+const COUNTDOWN_STEP: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Suspend,
+    Hibernate,
+    HybridSleep,
+    PowerOff
+}
+
+impl Action {
+    fn from_config(config: &Config) -> Option<Action> {
+        match config.get_str("power", "critical_action") {
+            Some("suspend") => Some(Action::Suspend),
+            Some("hibernate") => Some(Action::Hibernate),
+            Some("hybrid-sleep") => Some(Action::HybridSleep),
+            Some("poweroff") => Some(Action::PowerOff),
+            Some("none") | None => None,
+            Some(action) => {
+                error!("Unknown critical battery action: {}", action);
+                None
+            }
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match *self {
+            Action::Suspend => "suspend",
+            Action::Hibernate => "hibernate",
+            Action::HybridSleep => "hybrid sleep",
+            Action::PowerOff => "power off"
+        }
+    }
+}
+
+// Flags shared with a countdown thread
+struct Countdown {
+    cancelled: Arc<AtomicBool>,
+    // Set once the action ran, e.g. the system suspended and resumed
+    performed: Arc<AtomicBool>
+}
+
+// Calls a logind action once the battery reaches a critical level while
+// discharging. The action runs after a countdown notification that can be
+// cancelled from the notification or by plugging in AC.
+pub struct CriticalAction {
+    action: Action,
+    percentage: i64,
+    delay: u64,
+    // Countdown started during the current discharge
+    countdown: RefCell<Option<Countdown>>
+}
+
+impl CriticalAction {
+    pub fn new(config: &Config) -> Option<CriticalAction> {
+        let action = match Action::from_config(config) {
+            Some(action) => action,
+            None => return None
+        };
+        let delay = match config.get_i64("power", "critical_action_delay") {
+            Some(delay) if delay >= 0 => delay as u64,
+            _ => DEFAULT_ACTION_DELAY
+        };
+        Some(CriticalAction {
+            action: action,
+            percentage: config.get_i64("power", "critical_action_percentage")
+                .unwrap_or(DEFAULT_ACTION_PERCENTAGE),
+            delay: delay,
+            countdown: RefCell::new(None)
+        })
+    }
+
+    pub fn update(&self, state: i64, percentage: i64) {
+        match state {
+            DISCHARGING | EMPTY => {
+                // Start over when the battery is still critical after the
+                // action ran
+                let performed = match *self.countdown.borrow() {
+                    Some(ref countdown) => countdown.performed.load(Ordering::SeqCst),
+                    None => false
+                };
+                if performed {
+                    *self.countdown.borrow_mut() = None;
+                }
+                if percentage <= self.percentage && self.countdown.borrow().is_none() {
+                    let countdown = Countdown {
+                        cancelled: Arc::new(AtomicBool::new(false)),
+                        performed: Arc::new(AtomicBool::new(false))
+                    };
+                    let cancelled = countdown.cancelled.clone();
+                    let performed = countdown.performed.clone();
+                    let action = self.action;
+                    let delay = self.delay;
+                    info!("Battery critical, starting {} countdown", action.description());
+                    thread::spawn(move || {
+                        if run_countdown(action, delay, cancelled) {
+                            performed.store(true, Ordering::SeqCst);
+                        }
+                    });
+                    *self.countdown.borrow_mut() = Some(countdown);
+                }
+            },
+            CHARGING | FULLY_CHARGED | PENDING_CHARGE => {
+                if let Some(countdown) = self.countdown.borrow_mut().take() {
+                    info!("AC plugged in, cancelling critical battery action");
+                    countdown.cancelled.store(true, Ordering::SeqCst);
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+// Whether logind allows the action without asking. "challenge" needs
+// interactive authentication, which a status item cannot answer.
+fn can_perform(conn: &Connection, action: Action) -> bool {
+    let c_path = conn.with_path(
+        "org.freedesktop.login1", "/org/freedesktop/login1", 5000);
+    let result = match action {
+        Action::Suspend => c_path.can_suspend(),
+        Action::Hibernate => c_path.can_hibernate(),
+        Action::HybridSleep => c_path.can_hybrid_sleep(),
+        Action::PowerOff => c_path.can_power_off()
+    };
+    match result {
+        Ok(ref can) if can == "yes" => true,
+        Ok(can) => {
+            info!("logind cannot {} ({})", action.description(), can);
+            false
+        },
+        Err(err) => {
+            error!("Could not ask logind whether it can {}: {:?}", action.description(), err);
+            false
+        }
+    }
+}
+
+// Falls back to suspending when logind cannot hibernate. Powering off only
+// happens when it is configured.
+fn resolve_action(conn: &Connection, action: Action) -> Option<Action> {
+    if action == Action::PowerOff || can_perform(conn, action) {
+        Some(action)
+    } else if action != Action::Suspend && can_perform(conn, Action::Suspend) {
+        info!("Suspending instead of {}", action.description());
+        Some(Action::Suspend)
+    } else {
+        None
+    }
+}
+
+fn perform_action(conn: &Connection, action: Action) -> bool {
+    let c_path = conn.with_path(
+        "org.freedesktop.login1", "/org/freedesktop/login1", 25000);
+    let result = match action {
+        Action::Suspend => c_path.suspend(false),
+        Action::Hibernate => c_path.hibernate(false),
+        Action::HybridSleep => c_path.hybrid_sleep(false),
+        Action::PowerOff => c_path.power_off(false)
+    };
+    match result {
+        Ok(_) => {
+            info!("Critical battery action: {}", action.description());
+            true
+        },
+        Err(err) => {
+            error!("Could not {}: {:?}", action.description(), err);
+            false
+        }
+    }
+}
+
+fn notify_countdown(conn: &Connection, id: u32, action: Action, seconds: u64) -> u32 {
+    let body = format!("The computer will {} in {} seconds unless AC is plugged in",
+        action.description(), seconds);
+    notify::send_notification(conn, "Battery critically low", &body,
+        "battery-empty-symbolic", URGENCY_CRITICAL, id, vec!["cancel", "Cancel"])
+        .unwrap_or(id)
+}
+
+fn close_notification(conn: &Connection, id: u32) {
+    if id == 0 {
+        return
+    }
+    let c_path = conn.with_path(
+        "org.freedesktop.Notifications", "/org/freedesktop/Notifications", 5000);
+    if let Err(err) = c_path.close_notification(id) {
+        error!("Could not close critical action notification: {:?}", err);
+    }
+}
+
+// Returns whether the action was performed
+fn run_countdown(action: Action, delay: u64, cancelled: Arc<AtomicBool>) -> bool {
+    let (session, system) = match (Connection::get_private(BusType::Session),
+        Connection::get_private(BusType::System)) {
+        (Ok(session), Ok(system)) => (session, system),
+        (Err(err), _) | (_, Err(err)) => {
+            error!("Could not start critical action countdown: {:?}", err);
+            return false
+        }
+    };
+    let action = match resolve_action(&system, action) {
+        Some(action) => action,
+        None => {
+            info!("No critical battery action available, skipping {}", action.description());
+            return false
+        }
+    };
+    let rule = OrgFreedesktopNotificationsActionInvoked::match_str(None, None);
+    if let Err(err) = session.add_match(&rule) {
+        error!("Critical action countdown could not add dbus match: {:?}", err);
+    }
+    let deadline = Instant::now() + Duration::from_secs(delay);
+    let mut id = 0;
+    let mut shown_step = None;
+    loop {
+        if cancelled.load(Ordering::SeqCst) {
+            info!("Critical battery action cancelled");
+            close_notification(&session, id);
+            return false
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            break
+        }
+        let remaining = (deadline - now).as_secs() + 1;
+        let step = remaining / COUNTDOWN_STEP;
+        if shown_step != Some(step) {
+            id = notify_countdown(&session, id, action, remaining);
+            shown_step = Some(step);
+        }
+        for m in session.incoming(1000) {
+            if let Some(invoked) = OrgFreedesktopNotificationsActionInvoked::from_message(&m) {
+                if invoked.id == id && invoked.action_key == "cancel" {
+                    cancelled.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+    }
+    close_notification(&session, id);
+    perform_action(&system, action)
+}
@@ -0,0 +1,392 @@
+use libc;
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::PathBuf;
+use std::ptr;
+use std::time::{Duration, Instant};
+
+use config::Config;
+use notify;
+use notify::{URGENCY_CRITICAL, URGENCY_NORMAL};
+use throughput;
+use throughput::Counters;
+
+// Seconds between samples when the throughput item doesn't sample more
+// often
+pub const DEFAULT_INTERVAL: u64 = 60;
+
+// Seconds between writes of the state file
+const SAVE_INTERVAL: u64 = 60;
+
+const DEFAULT_WARNING_PERCENTAGE: i64 = 80;
+
+// Which connections data usage is counted for
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tracking {
+    Metered,
+    All
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Period {
+    Day,
+    Month
+}
+
+impl Period {
+    fn description(&self) -> &'static str {
+        match *self {
+            Period::Day => "today",
+            Period::Month => "this month"
+        }
+    }
+
+    // Key of the current day or month, dates are stored as "2024-05-17"
+    fn get_key(&self, date: &str) -> String {
+        match *self {
+            Period::Day => date.to_string(),
+            Period::Month => date.chars().take(7).collect()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+enum Level {
+    Normal,
+    Warning,
+    Exceeded
+}
+
+impl Level {
+    fn from_name(name: &str) -> Option<Level> {
+        match name {
+            "warning" => Some(Level::Warning),
+            "exceeded" => Some(Level::Exceeded),
+            _ => None
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            Level::Normal => "normal",
+            Level::Warning => "warning",
+            Level::Exceeded => "exceeded"
+        }
+    }
+}
+
+// Connection, period and level of the last cap notification
+type Notified = (String, String, Level);
+
+// Bytes received and sent over a connection on one day
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    uuid: String,
+    date: String,
+    rx_bytes: u64,
+    tx_bytes: u64
+}
+
+// Today's date in local time, e.g. "2024-05-17"
+fn get_local_date() -> Option<String> {
+    unsafe {
+        let now = libc::time(ptr::null_mut());
+        let mut tm: libc::tm = mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            return None
+        }
+        Some(format!("{:04}-{:02}-{:02}", tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday))
+    }
+}
+
+fn state_path() -> Option<PathBuf> {
+    let mut path = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".local").join("state"),
+            None => return None
+        }
+    };
+    path.push("sgstatus");
+    path.push("data-usage");
+    Some(path)
+}
+
+// One "UUID DATE RX TX" line per connection and day, and a "notified UUID
+// PERIOD LEVEL" line for the last cap notification so that it isn't sent
+// again after a restart
+fn parse_state(contents: &str) -> (Vec<Entry>, Option<Notified>) {
+    let mut notified = None;
+    let entries = contents.lines().filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["notified", uuid, key, level] => {
+                notified = Level::from_name(level)
+                    .map(|level| (uuid.to_string(), key.to_string(), level));
+                None
+            },
+            [uuid, date, rx_bytes, tx_bytes] => {
+                match (rx_bytes.parse(), tx_bytes.parse()) {
+                    (Ok(rx_bytes), Ok(tx_bytes)) => Some(Entry {
+                        uuid: uuid.to_string(),
+                        date: date.to_string(),
+                        rx_bytes: rx_bytes,
+                        tx_bytes: tx_bytes
+                    }),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }).collect();
+    (entries, notified)
+}
+
+fn format_state(entries: &[Entry], notified: &Option<Notified>) -> String {
+    let mut contents: String = entries.iter()
+        .map(|entry| format!("{} {} {} {}\n",
+            entry.uuid, entry.date, entry.rx_bytes, entry.tx_bytes))
+        .collect();
+    if let Some((ref uuid, ref key, level)) = *notified {
+        contents.push_str(&format!("notified {} {} {}\n", uuid, key, level.name()));
+    }
+    contents
+}
+
+// Bytes transferred per NetworkManager connection per day, persisted under
+// `$XDG_STATE_HOME/sgstatus` and compared against `[network] data_cap`.
+// Days before the current month are dropped.
+pub struct DataUsage {
+    path: Option<PathBuf>,
+    tracking: Tracking,
+    // Bytes allowed per period
+    cap: Option<u64>,
+    period: Period,
+    warning_percentage: i64,
+    entries: RefCell<Vec<Entry>>,
+    // Connection the counters were last read for
+    last: RefCell<Option<(String, Counters)>>,
+    last_save: Cell<Option<Instant>>,
+    unsaved: Cell<bool>,
+    // Level notified for a connection in a period
+    notified: RefCell<Option<Notified>>,
+    notification_id: Cell<u32>
+}
+
+impl DataUsage {
+    pub fn new(config: &Config) -> Option<DataUsage> {
+        let tracking = match config.get_str("network", "data_usage") {
+            Some("metered") => Tracking::Metered,
+            Some("all") => Tracking::All,
+            Some("off") | None => return None,
+            Some(tracking) => {
+                error!("Unknown data usage tracking: {}", tracking);
+                return None
+            }
+        };
+        let period = match config.get_str("network", "data_cap_period") {
+            Some("day") => Period::Day,
+            Some("month") | None => Period::Month,
+            Some(period) => {
+                error!("Unknown data cap period: {}", period);
+                Period::Month
+            }
+        };
+        let usage = DataUsage {
+            path: state_path(),
+            tracking: tracking,
+            cap: config.get_i64("network", "data_cap")
+                .filter(|cap| *cap > 0)
+                .map(|cap| cap as u64 * 1000 * 1000),
+            period: period,
+            warning_percentage: config.get_i64("network", "data_cap_warning")
+                .unwrap_or(DEFAULT_WARNING_PERCENTAGE),
+            entries: RefCell::new(Vec::new()),
+            last: RefCell::new(None),
+            last_save: Cell::new(None),
+            unsaved: Cell::new(false),
+            notified: RefCell::new(None),
+            notification_id: Cell::new(0)
+        };
+        usage.load();
+        Some(usage)
+    }
+
+    fn load(&self) {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return
+        };
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                let (entries, notified) = parse_state(&contents);
+                *self.entries.borrow_mut() = entries;
+                *self.notified.borrow_mut() = notified;
+            },
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {},
+            Err(err) => error!("Could not read data usage {:?}: {}", path, err)
+        }
+    }
+
+    fn save(&self) {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return
+        };
+        let result = match path.parent() {
+            Some(dir) => fs::create_dir_all(dir),
+            None => Ok(())
+        }.and_then(|_| fs::write(path,
+            format_state(&self.entries.borrow(), &self.notified.borrow())));
+        if let Err(err) = result {
+            error!("Could not save data usage {:?}: {}", path, err);
+        }
+        self.last_save.set(Some(Instant::now()));
+        self.unsaved.set(false);
+    }
+
+    // Whether usage is counted for a connection
+    pub fn is_tracked(&self, metered: bool) -> bool {
+        match self.tracking {
+            Tracking::Metered => metered,
+            Tracking::All => true
+        }
+    }
+
+    // Adds what was transferred on `interface` since the last sample to the
+    // connection's usage, the counters are only read while it is tracked.
+    // Usage is saved right away when the tracked connection changes or goes
+    // down. Returns whether the usage changed.
+    pub fn sample(&self, connection: Option<(&str, &str)>) -> bool {
+        let date = match get_local_date() {
+            Some(date) => date,
+            None => return false
+        };
+        let counters = connection.and_then(|(uuid, interface)| {
+            throughput::read_counters(interface).map(|counters| (uuid.to_string(), counters))
+        });
+        let last = self.last.replace(counters.clone());
+        let last_uuid = last.as_ref().map(|&(ref uuid, _)| uuid.clone());
+        let switched = last_uuid.is_some() &&
+            last_uuid != counters.as_ref().map(|&(ref uuid, _)| uuid.clone());
+        let added = match (last, counters) {
+            // Counters go back to 0 when a device is plugged in again
+            (Some((ref last_uuid, ref last)), Some((ref uuid, ref counters)))
+                if last_uuid == uuid && last.interface == counters.interface &&
+                counters.rx_bytes >= last.rx_bytes && counters.tx_bytes >= last.tx_bytes => {
+                self.add(uuid, &date, counters.rx_bytes - last.rx_bytes,
+                    counters.tx_bytes - last.tx_bytes)
+            },
+            _ => false
+        };
+        let month = Period::Month.get_key(&date);
+        let before = self.entries.borrow().len();
+        self.entries.borrow_mut().retain(|entry| entry.date.starts_with(&month));
+        let pruned = self.entries.borrow().len() != before;
+        if added || pruned {
+            self.unsaved.set(true);
+        }
+        let save_due = match self.last_save.get() {
+            Some(last_save) => last_save.elapsed() >= Duration::from_secs(SAVE_INTERVAL),
+            None => true
+        };
+        if self.unsaved.get() && (save_due || switched) {
+            self.save();
+        }
+        added
+    }
+
+    fn add(&self, uuid: &str, date: &str, rx_bytes: u64, tx_bytes: u64) -> bool {
+        if rx_bytes == 0 && tx_bytes == 0 {
+            return false
+        }
+        let mut entries = self.entries.borrow_mut();
+        match entries.iter().position(|entry| entry.uuid == uuid && entry.date == date) {
+            Some(index) => {
+                entries[index].rx_bytes += rx_bytes;
+                entries[index].tx_bytes += tx_bytes;
+            },
+            None => entries.push(Entry {
+                uuid: uuid.to_string(),
+                date: date.to_string(),
+                rx_bytes: rx_bytes,
+                tx_bytes: tx_bytes
+            })
+        }
+        true
+    }
+
+    // Bytes transferred over the connection in the current period
+    fn get_usage(&self, uuid: &str) -> Option<u64> {
+        let key = match get_local_date() {
+            Some(date) => self.period.get_key(&date),
+            None => return None
+        };
+        Some(self.entries.borrow().iter()
+            .filter(|entry| entry.uuid == uuid && entry.date.starts_with(&key))
+            .map(|entry| entry.rx_bytes + entry.tx_bytes)
+            .sum())
+    }
+
+    fn get_level(&self, usage: u64) -> Level {
+        match self.cap {
+            Some(cap) if usage >= cap => Level::Exceeded,
+            Some(cap) if usage as f64 >= cap as f64 * self.warning_percentage as f64 / 100.0 => {
+                Level::Warning
+            },
+            _ => Level::Normal
+        }
+    }
+
+    // Tooltip line for the connection
+    pub fn description(&self, uuid: &str) -> Option<String> {
+        let usage = match self.get_usage(uuid) {
+            Some(usage) => usage,
+            None => return None
+        };
+        let used = throughput::format_amount(usage as f64, "B");
+        Some(match self.cap {
+            Some(cap) => format!("Data used {}: {} of {} ({}%)", self.period.description(),
+                used, throughput::format_amount(cap as f64, "B"),
+                (usage as f64 * 100.0 / cap as f64).round()),
+            None => format!("Data used {}: {}", self.period.description(), used)
+        })
+    }
+
+    // Sends a desktop notification when the connection's usage gets close to
+    // the cap and when it goes over, once per connection and period
+    pub fn check_cap(&self, uuid: &str, name: &str) {
+        let (usage, key) = match (self.get_usage(uuid), get_local_date()) {
+            (Some(usage), Some(date)) => (usage, self.period.get_key(&date)),
+            _ => return
+        };
+        let level = self.get_level(usage);
+        let notified = match *self.notified.borrow() {
+            Some((ref notified_uuid, ref notified_key, notified_level))
+                if notified_uuid == uuid && *notified_key == key => notified_level,
+            _ => Level::Normal
+        };
+        if level <= notified {
+            return
+        }
+        *self.notified.borrow_mut() = Some((uuid.to_string(), key, level));
+        // Saved with the usage that crossed the level
+        self.save();
+        let (summary, icon, urgency) = match level {
+            Level::Exceeded => ("Data cap reached", "dialog-warning-symbolic", URGENCY_CRITICAL),
+            _ => ("Data cap almost reached", "network-transmit-receive-symbolic", URGENCY_NORMAL)
+        };
+        let body = match self.description(uuid) {
+            Some(description) => format!("{}\n{}", name, description),
+            None => String::from(name)
+        };
+        if let Some(id) = notify::notify(summary, &body, icon, urgency,
+            self.notification_id.get()) {
+            self.notification_id.set(id);
+        }
+    }
+}
@@ -0,0 +1,363 @@
+// This code was autogenerated with dbus-codegen-rust, see https://github.com/diwic/dbus-rs
+
+#![allow(dead_code)]
+use dbus as dbus;
+use dbus::arg;
+use dbus::tree;
+
+pub trait ComCanonicalDbusmenu {
+    type Err;
+    fn get_layout(&self, parent_id: i32, recursion_depth: i32, property_names: Vec<&str>) -> Result<(u32, (i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>, Vec<arg::Variant<Box<arg::RefArg>>>)), Self::Err>;
+    fn get_group_properties(&self, ids: Vec<i32>, property_names: Vec<&str>) -> Result<Vec<(i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>)>, Self::Err>;
+    fn get_property(&self, id: i32, name: &str) -> Result<arg::Variant<Box<arg::RefArg>>, Self::Err>;
+    fn event(&self, id: i32, event_id: &str, data: arg::Variant<Box<arg::RefArg>>, timestamp: u32) -> Result<(), Self::Err>;
+    fn event_group(&self, events: Vec<(i32, &str, arg::Variant<Box<arg::RefArg>>, u32)>) -> Result<Vec<i32>, Self::Err>;
+    fn about_to_show(&self, id: i32) -> Result<bool, Self::Err>;
+    fn about_to_show_group(&self, ids: Vec<i32>) -> Result<(Vec<i32>, Vec<i32>), Self::Err>;
+    fn get_version(&self) -> Result<u32, Self::Err>;
+    fn get_text_direction(&self) -> Result<String, Self::Err>;
+    fn get_status(&self) -> Result<String, Self::Err>;
+    fn get_icon_theme_path(&self) -> Result<Vec<String>, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> ComCanonicalDbusmenu for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_layout(&self, parent_id: i32, recursion_depth: i32, property_names: Vec<&str>) -> Result<(u32, (i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>, Vec<arg::Variant<Box<arg::RefArg>>>)), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"GetLayout".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(parent_id);
+            i.append(recursion_depth);
+            i.append(property_names);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let revision: u32 = try!(i.read());
+        let layout: (i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>, Vec<arg::Variant<Box<arg::RefArg>>>) = try!(i.read());
+        Ok((revision, layout))
+    }
+
+    fn get_group_properties(&self, ids: Vec<i32>, property_names: Vec<&str>) -> Result<Vec<(i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>)>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"GetGroupProperties".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(ids);
+            i.append(property_names);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let properties: Vec<(i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>)> = try!(i.read());
+        Ok(properties)
+    }
+
+    fn get_property(&self, id: i32, name: &str) -> Result<arg::Variant<Box<arg::RefArg>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"GetProperty".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(id);
+            i.append(name);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let value: arg::Variant<Box<arg::RefArg>> = try!(i.read());
+        Ok(value)
+    }
+
+    fn event(&self, id: i32, event_id: &str, data: arg::Variant<Box<arg::RefArg>>, timestamp: u32) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"Event".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(id);
+            i.append(event_id);
+            i.append(data);
+            i.append(timestamp);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn event_group(&self, events: Vec<(i32, &str, arg::Variant<Box<arg::RefArg>>, u32)>) -> Result<Vec<i32>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"EventGroup".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(events);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let id_errors: Vec<i32> = try!(i.read());
+        Ok(id_errors)
+    }
+
+    fn about_to_show(&self, id: i32) -> Result<bool, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"AboutToShow".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(id);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let need_update: bool = try!(i.read());
+        Ok(need_update)
+    }
+
+    fn about_to_show_group(&self, ids: Vec<i32>) -> Result<(Vec<i32>, Vec<i32>), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"com.canonical.dbusmenu".into(), &"AboutToShowGroup".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(ids);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let updates_needed: Vec<i32> = try!(i.read());
+        let id_errors: Vec<i32> = try!(i.read());
+        Ok((updates_needed, id_errors))
+    }
+
+    fn get_version(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "com.canonical.dbusmenu", "Version")
+    }
+
+    fn get_text_direction(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "com.canonical.dbusmenu", "TextDirection")
+    }
+
+    fn get_status(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "com.canonical.dbusmenu", "Status")
+    }
+
+    fn get_icon_theme_path(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "com.canonical.dbusmenu", "IconThemePath")
+    }
+}
+
+pub fn com_canonical_dbusmenu_server<F, T, D>(factory: &tree::Factory<tree::MTFn<D>, D>, data: D::Interface, f: F) -> tree::Interface<tree::MTFn<D>, D>
+where
+    D: tree::DataType,
+    D::Method: Default,
+    D::Property: Default,
+    T: ComCanonicalDbusmenu<Err=tree::MethodErr>,
+    F: 'static + for <'z> Fn(& 'z tree::MethodInfo<tree::MTFn<D>, D>) -> & 'z T,
+{
+    let i = factory.interface("com.canonical.dbusmenu", data);
+    let f = ::std::sync::Arc::new(f);
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let parent_id: i32 = try!(i.read());
+        let recursion_depth: i32 = try!(i.read());
+        let property_names: Vec<&str> = try!(i.read());
+        let d = fclone(minfo);
+        let (revision, layout) = try!(d.get_layout(parent_id, recursion_depth, property_names));
+        let rm = minfo.msg.method_return();
+        let rm = rm.append1(revision);
+        let rm = rm.append1(layout);
+        Ok(vec!(rm))
+    };
+    let m = factory.method("GetLayout", Default::default(), h);
+    let m = m.in_arg(("parentId", "i"));
+    let m = m.in_arg(("recursionDepth", "i"));
+    let m = m.in_arg(("propertyNames", "as"));
+    let m = m.out_arg(("revision", "u"));
+    let m = m.out_arg(("layout", "(ia{sv}av)"));
+    let i = i.add_m(m);
+
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let ids: Vec<i32> = try!(i.read());
+        let property_names: Vec<&str> = try!(i.read());
+        let d = fclone(minfo);
+        let properties = try!(d.get_group_properties(ids, property_names));
+        let rm = minfo.msg.method_return();
+        let rm = rm.append1(properties);
+        Ok(vec!(rm))
+    };
+    let m = factory.method("GetGroupProperties", Default::default(), h);
+    let m = m.in_arg(("ids", "ai"));
+    let m = m.in_arg(("propertyNames", "as"));
+    let m = m.out_arg(("properties", "a(ia{sv})"));
+    let i = i.add_m(m);
+
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let id: i32 = try!(i.read());
+        let name: &str = try!(i.read());
+        let d = fclone(minfo);
+        let value = try!(d.get_property(id, name));
+        let rm = minfo.msg.method_return();
+        let rm = rm.append1(value);
+        Ok(vec!(rm))
+    };
+    let m = factory.method("GetProperty", Default::default(), h);
+    let m = m.in_arg(("id", "i"));
+    let m = m.in_arg(("name", "s"));
+    let m = m.out_arg(("value", "v"));
+    let i = i.add_m(m);
+
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let id: i32 = try!(i.read());
+        let event_id: &str = try!(i.read());
+        let data: arg::Variant<Box<arg::RefArg>> = try!(i.read());
+        let timestamp: u32 = try!(i.read());
+        let d = fclone(minfo);
+        try!(d.event(id, event_id, data, timestamp));
+        let rm = minfo.msg.method_return();
+        Ok(vec!(rm))
+    };
+    let m = factory.method("Event", Default::default(), h);
+    let m = m.in_arg(("id", "i"));
+    let m = m.in_arg(("eventId", "s"));
+    let m = m.in_arg(("data", "v"));
+    let m = m.in_arg(("timestamp", "u"));
+    let i = i.add_m(m);
+
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let events: Vec<(i32, &str, arg::Variant<Box<arg::RefArg>>, u32)> = try!(i.read());
+        let d = fclone(minfo);
+        let id_errors = try!(d.event_group(events));
+        let rm = minfo.msg.method_return();
+        let rm = rm.append1(id_errors);
+        Ok(vec!(rm))
+    };
+    let m = factory.method("EventGroup", Default::default(), h);
+    let m = m.in_arg(("events", "a(isvu)"));
+    let m = m.out_arg(("idErrors", "ai"));
+    let i = i.add_m(m);
+
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let id: i32 = try!(i.read());
+        let d = fclone(minfo);
+        let need_update = try!(d.about_to_show(id));
+        let rm = minfo.msg.method_return();
+        let rm = rm.append1(need_update);
+        Ok(vec!(rm))
+    };
+    let m = factory.method("AboutToShow", Default::default(), h);
+    let m = m.in_arg(("id", "i"));
+    let m = m.out_arg(("needUpdate", "b"));
+    let i = i.add_m(m);
+
+    let fclone = f.clone();
+    let h = move |minfo: &tree::MethodInfo<tree::MTFn<D>, D>| {
+        let mut i = minfo.msg.iter_init();
+        let ids: Vec<i32> = try!(i.read());
+        let d = fclone(minfo);
+        let (updates_needed, id_errors) = try!(d.about_to_show_group(ids));
+        let rm = minfo.msg.method_return();
+        let rm = rm.append1(updates_needed);
+        let rm = rm.append1(id_errors);
+        Ok(vec!(rm))
+    };
+    let m = factory.method("AboutToShowGroup", Default::default(), h);
+    let m = m.in_arg(("ids", "ai"));
+    let m = m.out_arg(("updatesNeeded", "ai"));
+    let m = m.out_arg(("idErrors", "ai"));
+    let i = i.add_m(m);
+
+    let p = factory.property::<u32, _>("Version", Default::default());
+    let p = p.access(tree::Access::Read);
+    let fclone = f.clone();
+    let p = p.on_get(move |a, pinfo| {
+        let minfo = pinfo.to_method_info();
+        let d = fclone(&minfo);
+        a.append(try!(d.get_version()));
+        Ok(())
+    });
+    let i = i.add_p(p);
+
+    let p = factory.property::<&str, _>("TextDirection", Default::default());
+    let p = p.access(tree::Access::Read);
+    let fclone = f.clone();
+    let p = p.on_get(move |a, pinfo| {
+        let minfo = pinfo.to_method_info();
+        let d = fclone(&minfo);
+        a.append(try!(d.get_text_direction()));
+        Ok(())
+    });
+    let i = i.add_p(p);
+
+    let p = factory.property::<&str, _>("Status", Default::default());
+    let p = p.access(tree::Access::Read);
+    let fclone = f.clone();
+    let p = p.on_get(move |a, pinfo| {
+        let minfo = pinfo.to_method_info();
+        let d = fclone(&minfo);
+        a.append(try!(d.get_status()));
+        Ok(())
+    });
+    let i = i.add_p(p);
+
+    let p = factory.property::<Vec<&str>, _>("IconThemePath", Default::default());
+    let p = p.access(tree::Access::Read);
+    let fclone = f.clone();
+    let p = p.on_get(move |a, pinfo| {
+        let minfo = pinfo.to_method_info();
+        let d = fclone(&minfo);
+        a.append(try!(d.get_icon_theme_path()));
+        Ok(())
+    });
+    let i = i.add_p(p);
+
+    i
+}
+
+#[derive(Debug, Default)]
+pub struct ComCanonicalDbusmenuItemsPropertiesUpdated {
+    pub updated_props: Vec<(i32, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>)>,
+    pub removed_props: Vec<(i32, Vec<String>)>,
+}
+
+impl dbus::SignalArgs for ComCanonicalDbusmenuItemsPropertiesUpdated {
+    const NAME: &'static str = "ItemsPropertiesUpdated";
+    const INTERFACE: &'static str = "com.canonical.dbusmenu";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.updated_props as &arg::RefArg).append(i);
+        (&self.removed_props as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.updated_props = try!(i.read());
+        self.removed_props = try!(i.read());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ComCanonicalDbusmenuLayoutUpdated {
+    pub revision: u32,
+    pub parent: i32,
+}
+
+impl dbus::SignalArgs for ComCanonicalDbusmenuLayoutUpdated {
+    const NAME: &'static str = "LayoutUpdated";
+    const INTERFACE: &'static str = "com.canonical.dbusmenu";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.revision as &arg::RefArg).append(i);
+        (&self.parent as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.revision = try!(i.read());
+        self.parent = try!(i.read());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ComCanonicalDbusmenuItemActivationRequested {
+    pub id: i32,
+    pub timestamp: u32,
+}
+
+impl dbus::SignalArgs for ComCanonicalDbusmenuItemActivationRequested {
+    const NAME: &'static str = "ItemActivationRequested";
+    const INTERFACE: &'static str = "com.canonical.dbusmenu";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.id as &arg::RefArg).append(i);
+        (&self.timestamp as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.id = try!(i.read());
+        self.timestamp = try!(i.read());
+        Ok(())
+    }
+}
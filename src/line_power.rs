@@ -0,0 +1,67 @@
+use dbus::Connection;
+
+use std::collections::HashMap;
+use upower::OrgFreedesktopUPower;
+use upower::OrgFreedesktopUPowerDevice;
+use upower::OrgFreedesktopDBusPropertiesPropertiesChanged;
+
+// Type constants
+// https://upower.freedesktop.org/docs/Device.html#Device:Type
+const TYPE_LINE_POWER: u32 = 1;
+
+// Online state of the AC adapters UPower knows about
+#[derive(Debug, Default)]
+pub struct LinePower {
+    online: HashMap<String, bool>
+}
+
+impl LinePower {
+    pub fn load(conn: &Connection) -> LinePower {
+        let c_path = conn.with_path(
+            "org.freedesktop.UPower", "/org/freedesktop/UPower", 5000);
+        let devices = match c_path.enumerate_devices() {
+            Ok(devices) => devices,
+            Err(err) => {
+                error!("Could not enumerate UPower devices: {:?}", err);
+                Vec::new()
+            }
+        };
+        let mut online = HashMap::new();
+        for path in devices.iter() {
+            let c_path = conn.with_path("org.freedesktop.UPower", path.to_string(), 5000);
+            match (c_path.get_type(), c_path.get_power_supply()) {
+                (Ok(TYPE_LINE_POWER), Ok(true)) => {
+                    online.insert(path.to_string(), c_path.get_online().unwrap_or(false));
+                },
+                _ => {}
+            }
+        }
+        LinePower {
+            online: online
+        }
+    }
+
+    pub fn get_paths(&self) -> Vec<String> {
+        self.online.keys().cloned().collect()
+    }
+
+    // Updates the adapter at `path`, returns whether its online state changed
+    pub fn update(&mut self, path: &str, changed: &OrgFreedesktopDBusPropertiesPropertiesChanged) -> bool {
+        let online = match changed.changed_properties.get("Online") {
+            Some(value) => value.0.as_i64() == Some(1),
+            None => return false
+        };
+        match self.online.insert(path.to_string(), online) {
+            Some(previous) => previous != online,
+            None => true
+        }
+    }
+
+    // None when there is no AC adapter to ask
+    pub fn is_online(&self) -> Option<bool> {
+        if self.online.is_empty() {
+            return None
+        }
+        Some(self.online.values().any(|online| *online))
+    }
+}
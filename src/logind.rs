@@ -0,0 +1,93 @@
+// This code was autogenerated with dbus-codegen-rust, see https://github.com/diwic/dbus-rs
+
+#![allow(dead_code)]
+use dbus as dbus;
+use dbus::arg;
+
+pub trait OrgFreedesktopLogin1Manager {
+    type Err;
+    fn power_off(&self, interactive: bool) -> Result<(), Self::Err>;
+    fn suspend(&self, interactive: bool) -> Result<(), Self::Err>;
+    fn hibernate(&self, interactive: bool) -> Result<(), Self::Err>;
+    fn hybrid_sleep(&self, interactive: bool) -> Result<(), Self::Err>;
+    fn can_power_off(&self) -> Result<String, Self::Err>;
+    fn can_suspend(&self) -> Result<String, Self::Err>;
+    fn can_hibernate(&self) -> Result<String, Self::Err>;
+    fn can_hybrid_sleep(&self) -> Result<String, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopLogin1Manager for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn power_off(&self, interactive: bool) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"PowerOff".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interactive);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn suspend(&self, interactive: bool) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"Suspend".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interactive);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn hibernate(&self, interactive: bool) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"Hibernate".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interactive);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn hybrid_sleep(&self, interactive: bool) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"HybridSleep".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interactive);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn can_power_off(&self) -> Result<String, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"CanPowerOff".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let result: String = try!(i.read());
+        Ok(result)
+    }
+
+    fn can_suspend(&self) -> Result<String, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"CanSuspend".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let result: String = try!(i.read());
+        Ok(result)
+    }
+
+    fn can_hibernate(&self) -> Result<String, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"CanHibernate".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let result: String = try!(i.read());
+        Ok(result)
+    }
+
+    fn can_hybrid_sleep(&self) -> Result<String, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.login1.Manager".into(), &"CanHybridSleep".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let result: String = try!(i.read());
+        Ok(result)
+    }
+}
@@ -0,0 +1,120 @@
+use dbus::Connection;
+use dbus::BusType;
+use dbus::Error;
+
+use std::cell::Cell;
+use config::Config;
+use notifications::OrgFreedesktopNotifications;
+use notify;
+use notify::{URGENCY_CRITICAL, URGENCY_NORMAL};
+use power::{CHARGING, DISCHARGING, EMPTY, FULLY_CHARGED, PENDING_CHARGE};
+use power::{WARNING_LEVEL_LOW, WARNING_LEVEL_CRITICAL, WARNING_LEVEL_ACTION};
+
+const DEFAULT_LOW_PERCENTAGE: i64 = 10;
+const DEFAULT_CRITICAL_PERCENTAGE: i64 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+enum Level {
+    Normal,
+    Low,
+    Critical
+}
+
+// Sends desktop notifications when the battery goes low or critical while
+// discharging. A notification is only sent when the level gets worse, each
+// new one replaces the previous and plugging in AC closes it.
+pub struct LowBatteryNotifier {
+    conn: Connection,
+    low_percentage: i64,
+    critical_percentage: i64,
+    use_warning_level: bool,
+    level: Cell<Level>,
+    notification_id: Cell<u32>
+}
+
+impl LowBatteryNotifier {
+    pub fn new(config: &Config) -> Result<LowBatteryNotifier, Error> {
+        match Connection::get_private(BusType::Session) {
+            Ok(c) => {
+                Ok(LowBatteryNotifier {
+                    conn: c,
+                    low_percentage: config.get_i64("power", "low_percentage")
+                        .unwrap_or(DEFAULT_LOW_PERCENTAGE),
+                    critical_percentage: config.get_i64("power", "critical_percentage")
+                        .unwrap_or(DEFAULT_CRITICAL_PERCENTAGE),
+                    use_warning_level: config.get_str("power", "notify_on") == Some("warning-level"),
+                    level: Cell::new(Level::Normal),
+                    notification_id: Cell::new(0)
+                })
+            },
+            Err(err) => {
+                Err(err)
+            }
+        }
+    }
+
+    // Whether the caller needs to pass UPower's WarningLevel to `update`
+    pub fn uses_warning_level(&self) -> bool {
+        self.use_warning_level
+    }
+
+    fn get_level(&self, percentage: i64, warning_level: Option<u32>) -> Level {
+        if self.use_warning_level {
+            match warning_level {
+                Some(WARNING_LEVEL_CRITICAL) | Some(WARNING_LEVEL_ACTION) => Level::Critical,
+                Some(WARNING_LEVEL_LOW) => Level::Low,
+                _ => Level::Normal
+            }
+        } else if percentage <= self.critical_percentage {
+            Level::Critical
+        } else if percentage <= self.low_percentage {
+            Level::Low
+        } else {
+            Level::Normal
+        }
+    }
+
+    fn notify(&self, level: Level, percentage: i64) {
+        let (summary, icon, urgency) = match level {
+            Level::Critical => ("Battery critically low", "battery-caution-symbolic", URGENCY_CRITICAL),
+            _ => ("Battery low", "battery-low-symbolic", URGENCY_NORMAL)
+        };
+        let body = format!("{}% of battery remaining", percentage);
+        if let Some(id) = notify::send_notification(&self.conn, summary, &body, icon,
+            urgency, self.notification_id.get(), Vec::new()) {
+            self.notification_id.set(id);
+        }
+    }
+
+    fn dismiss(&self) {
+        let id = self.notification_id.get();
+        if id == 0 {
+            return
+        }
+        let c_path = self.conn.with_path(
+            "org.freedesktop.Notifications", "/org/freedesktop/Notifications", 5000);
+        match c_path.close_notification(id) {
+            Ok(_) => info!("Closed low battery notification"),
+            Err(err) => error!("Could not close low battery notification: {:?}", err)
+        }
+        self.notification_id.set(0);
+    }
+
+    pub fn update(&self, state: i64, percentage: i64, warning_level: Option<u32>) {
+        match state {
+            DISCHARGING | EMPTY => {},
+            CHARGING | FULLY_CHARGED | PENDING_CHARGE => {
+                // AC was plugged in
+                self.dismiss();
+                self.level.set(Level::Normal);
+                return
+            },
+            _ => return
+        }
+        let level = self.get_level(percentage, warning_level);
+        if level > self.level.get() {
+            self.notify(level, percentage);
+        }
+        self.level.set(level);
+    }
+}
@@ -5,29 +5,67 @@ extern crate dbus_tokio;
 extern crate tokio_core;
 extern crate tokio_timer;
 extern crate futures;
+extern crate libc;
 extern crate env_logger;
 extern crate libpulse_binding as pulse;
 
+mod active_connection;
+mod battery_health;
+mod battery_history;
+mod charge_threshold;
+mod config;
+mod connection_details;
+mod critical_action;
+mod data_usage;
+mod dbus_menu;
+mod line_power;
+mod logind;
+mod low_battery;
+mod menu;
 mod network;
+mod network_devices;
 mod network_manager;
+mod notifications;
+mod notify;
+mod output;
+mod peripherals;
 mod power;
+mod power_draw;
+mod power_profiles;
+mod power_profiles_daemon;
+mod properties;
+mod radio;
+mod saved_connection;
 mod upower;
 mod sni_item;
 mod sni_watcher;
 mod status;
+mod throughput;
 mod volume; 
+mod vpn;
+mod wifi;
 
 use std::sync::mpsc::{Sender, Receiver};
 use std::sync::mpsc::*;
 use std::thread;
 use std::sync::*;
 
+use config::Config;
+use output::Block;
+use status::StatusUpdate;
+
 // Global channel to give access to pulse C callbacks (terrible but werx4now)
-pub static mut VOLUME_TX: Option<Mutex<Sender<String>>> = None;
+pub static mut VOLUME_TX: Option<Mutex<Sender<StatusUpdate>>> = None;
 
 fn main() {
     std::env::set_var("RUST_LOG", "info");
     env_logger::init();
+    let config = Config::load();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(run_command(&args, &config));
+    }
     
     let (mut volume_tx, volume_rx) = channel();
     #[allow(unused)]
@@ -35,23 +73,51 @@ fn main() {
         VOLUME_TX = Some(Mutex::new(volume_tx));
         volume_tx = VOLUME_TX.as_ref().unwrap().lock().unwrap().clone();
     }
-    let (network_tx, network_rx): (Sender<String>, Receiver<String>) = mpsc::channel();
-    let (power_tx, power_rx): (Sender<String>, Receiver<String>) = mpsc::channel();
+    let (network_tx, network_rx): (Sender<StatusUpdate>, Receiver<StatusUpdate>) = mpsc::channel();
+    let (vpn_tx, vpn_rx): (Sender<StatusUpdate>, Receiver<StatusUpdate>) = mpsc::channel();
+    let (throughput_tx, throughput_rx): (Sender<StatusUpdate>, Receiver<StatusUpdate>) = mpsc::channel();
+    let (power_tx, power_rx): (Sender<StatusUpdate>, Receiver<StatusUpdate>) = mpsc::channel();
+    let (profiles_tx, profiles_rx): (Sender<StatusUpdate>, Receiver<StatusUpdate>) = mpsc::channel();
+    let (text_tx, text_rx): (Sender<Block>, Receiver<Block>) = mpsc::channel();
+
+    let output_config = config.clone();
+    thread::spawn(move || {
+        output::write_status_line(text_rx, output_config);
+    });
 
     thread::spawn(move || {
         volume::monitor_volume();
     });
 
+    let network_config = config.clone();
     thread::spawn(move || {
-        network::monitor_network(network_tx);
+        network::monitor_network(network_tx, vpn_tx, throughput_tx, network_config);
     });
 
+    let power_config = config.clone();
     thread::spawn(move || {
-        power::monitor_power(power_tx);
+        power::monitor_power(power_tx, power_config);
     });
 
     thread::spawn(move || {
-        if let Ok(mut volume_notifier) = status::StatusNotifier::new() {
+        power_profiles::monitor_power_profiles(profiles_tx);
+    });
+
+    if config.get_bool("network", "per_device").unwrap_or(false) {
+        let devices_text_tx = text_tx.clone();
+        thread::spawn(move || {
+            network_devices::monitor_network_devices(devices_text_tx);
+        });
+    }
+
+    let peripherals_text_tx = text_tx.clone();
+    thread::spawn(move || {
+        peripherals::monitor_peripherals(peripherals_text_tx);
+    });
+
+    let volume_text_tx = text_tx.clone();
+    thread::spawn(move || {
+        if let Ok(mut volume_notifier) = status::StatusNotifier::new("volume", volume_text_tx) {
             info!("Starting volume notifier");
             if let Err(err) = volume_notifier.run(volume_rx) {
                 error!("Could not start volume notifier: {:?}", err)
@@ -59,18 +125,51 @@ fn main() {
         }    
     });
 
+    let network_text_tx = text_tx.clone();
+    let network_notifier_config = config.clone();
     thread::spawn(move || {
-        if let Ok(mut network_notifier) = status::StatusNotifier::new() {
+        if let Ok(mut network_notifier) = status::StatusNotifier::new("network", network_text_tx) {
             info!("Starting network notifier");
+            match network::get_action_handler(&network_notifier_config) {
+                Ok(handler) => network_notifier.set_action_handler(handler),
+                Err(err) => error!("Could not set network actions: {:?}", err)
+            }
             if let Err(err) = network_notifier.run(network_rx) {
                 error!("Could not start network notifier: {:?}", err)
             }
         }    
     });
 
+    let vpn_text_tx = text_tx.clone();
+    thread::spawn(move || {
+        if let Ok(mut vpn_notifier) = status::StatusNotifier::new("vpn", vpn_text_tx) {
+            info!("Starting VPN notifier");
+            if let Err(err) = vpn_notifier.run(vpn_rx) {
+                error!("Could not start VPN notifier: {:?}", err)
+            }
+        }
+    });
+
+    if config.get_bool("network", "throughput").unwrap_or(false) {
+        let throughput_text_tx = text_tx.clone();
+        thread::spawn(move || {
+            if let Ok(mut throughput_notifier) = status::StatusNotifier::new("throughput", throughput_text_tx) {
+                info!("Starting throughput notifier");
+                if let Err(err) = throughput_notifier.run(throughput_rx) {
+                    error!("Could not start throughput notifier: {:?}", err)
+                }
+            }
+        });
+    }
+
+    let power_text_tx = text_tx.clone();
+    let power_notifier_config = config.clone();
     thread::spawn(move || {
-        if let Ok(mut power_notifier) = status::StatusNotifier::new() {
+        if let Ok(mut power_notifier) = status::StatusNotifier::new("power", power_text_tx) {
             info!("Starting power notifier");
+            if let Some(handler) = charge_threshold::get_action_handler(&power_notifier_config) {
+                power_notifier.set_action_handler(handler);
+            }
             if let Err(err) = power_notifier.run(power_rx) {
                 error!("Could not start power notifier: {:?}", err)
             }
@@ -78,8 +177,41 @@ fn main() {
         
     });
 
+    let profiles_text_tx = text_tx.clone();
+    thread::spawn(move || {
+        if let Ok(mut profiles_notifier) = status::StatusNotifier::new("power-profile", profiles_text_tx) {
+            info!("Starting power profile notifier");
+            match power_profiles::get_action_handler() {
+                Ok(handler) => profiles_notifier.set_action_handler(handler),
+                Err(err) => error!("Could not set power profile actions: {:?}", err)
+            }
+            if let Err(err) = profiles_notifier.run(profiles_rx) {
+                error!("Could not start power profile notifier: {:?}", err)
+            }
+        }
+    });
+
     loop {
         std::thread::sleep(std::time::Duration::new(2, 0));
     }
 
 }
+
+// Runs a one-shot command instead of the status monitor, returns the exit
+// code
+fn run_command(args: &[String], config: &Config) -> i32 {
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    match args.as_slice() {
+        ["battery", "--health"] => {
+            battery_health::print_health_report(
+                battery_health::get_warning_percentage(config))
+        },
+        ["battery", "--set-thresholds", start, end] => {
+            charge_threshold::run_set_thresholds(start, end)
+        },
+        _ => {
+            eprintln!("Usage: sgstatus [battery --health | battery --set-thresholds START END]");
+            2
+        }
+    }
+}
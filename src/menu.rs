@@ -0,0 +1,275 @@
+use dbus::arg::{RefArg, Variant};
+
+use std::collections::HashMap;
+
+// Item of a status item's menu, served over com.canonical.dbusmenu
+// https://github.com/AyatanaIndicators/libdbusmenu/blob/master/libdbusmenu-glib/dbus-menu.xml
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MenuItem {
+    pub label: String,
+    // Sent to the item's action handler when the entry is clicked, entries
+    // without an action are shown greyed out
+    pub action: Option<String>,
+    // Shows a check mark next to the label when set
+    pub checked: Option<bool>,
+    pub separator: bool,
+    pub children: Vec<MenuItem>
+}
+
+impl MenuItem {
+    // An entry that only shows information
+    pub fn new(label: String) -> MenuItem {
+        MenuItem {
+            label: label,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_action(label: String, action: String) -> MenuItem {
+        MenuItem {
+            label: label,
+            action: Some(action),
+            ..Default::default()
+        }
+    }
+
+    pub fn separator() -> MenuItem {
+        MenuItem {
+            separator: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn submenu(label: String, children: Vec<MenuItem>) -> MenuItem {
+        MenuItem {
+            label: label,
+            children: children,
+            ..Default::default()
+        }
+    }
+}
+
+// Layout of a menu item as sent by GetLayout: id, properties and children
+pub type Layout = (i32, HashMap<String, Variant<Box<RefArg>>>, Vec<Variant<Box<RefArg>>>);
+
+// Ids of the entries of a status item's menu. Hosts fetch the layout and
+// send clicks by id later, after the menu may have been rebuilt, so an entry
+// keeps its id for as long as it stays in the menu and ids are never reused.
+#[derive(Debug)]
+pub struct MenuIds {
+    ids: HashMap<String, i32>,
+    next_id: i32
+}
+
+impl MenuIds {
+    pub fn new() -> MenuIds {
+        MenuIds {
+            ids: HashMap::new(),
+            // 0 is the root
+            next_id: 1
+        }
+    }
+
+    // Gives the entries of a new menu their ids, entries that were in the
+    // previous menu keep theirs
+    pub fn update(&mut self, items: &[MenuItem]) {
+        let mut keys = Vec::new();
+        collect_keys(items, "", &mut HashMap::new(), &mut keys);
+        let mut ids = HashMap::new();
+        for key in keys {
+            let id = match self.ids.get(&key) {
+                Some(id) => *id,
+                None => {
+                    self.next_id += 1;
+                    self.next_id - 1
+                }
+            };
+            ids.insert(key, id);
+        }
+        self.ids = ids;
+    }
+}
+
+// Identifies an entry across rebuilds by its action, or by its label and the
+// submenu it is in. Repeated keys get the number of the repetition.
+fn get_key(seen: &mut HashMap<String, usize>, parent: &str, item: &MenuItem) -> String {
+    let key = match item.action {
+        Some(ref action) => format!("action:{}", action),
+        None if item.separator => format!("{}/-", parent),
+        None => format!("{}/{}", parent, item.label)
+    };
+    let count = seen.entry(key.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 { key } else { format!("{}#{}", key, count) }
+}
+
+fn collect_keys(items: &[MenuItem], parent: &str, seen: &mut HashMap<String, usize>,
+    keys: &mut Vec<String>) {
+    for item in items.iter() {
+        let key = get_key(seen, parent, item);
+        keys.push(key.clone());
+        collect_keys(&item.children, &key, seen, keys);
+    }
+}
+
+// A menu item with the ids of its children
+struct Node<'a> {
+    item: Option<&'a MenuItem>,
+    children: Vec<i32>
+}
+
+fn add_nodes<'a>(nodes: &mut HashMap<i32, Node<'a>>, ids: &MenuIds, parent: &str,
+    seen: &mut HashMap<String, usize>, items: &'a [MenuItem]) -> Vec<i32> {
+    items.iter().filter_map(|item| {
+        let key = get_key(seen, parent, item);
+        // Entries of a menu the ids weren't updated for are left out
+        let id = match ids.ids.get(&key) {
+            Some(id) => *id,
+            None => return None
+        };
+        let children = add_nodes(nodes, ids, &key, seen, &item.children);
+        nodes.insert(id, Node {
+            item: Some(item),
+            children: children
+        });
+        Some(id)
+    }).collect()
+}
+
+fn get_nodes<'a>(items: &'a [MenuItem], ids: &MenuIds) -> HashMap<i32, Node<'a>> {
+    let mut nodes = HashMap::new();
+    let children = add_nodes(&mut nodes, ids, "", &mut HashMap::new(), items);
+    nodes.insert(0, Node {
+        item: None,
+        children: children
+    });
+    nodes
+}
+
+fn variant<T: RefArg + 'static>(value: T) -> Variant<Box<RefArg>> {
+    Variant(Box::new(value) as Box<RefArg>)
+}
+
+fn get_node_properties(node: &Node) -> HashMap<String, Variant<Box<RefArg>>> {
+    let mut properties = HashMap::new();
+    if !node.children.is_empty() {
+        properties.insert(String::from("children-display"), variant(String::from("submenu")));
+    }
+    if let Some(item) = node.item {
+        if item.separator {
+            properties.insert(String::from("type"), variant(String::from("separator")));
+            return properties
+        }
+        properties.insert(String::from("label"), variant(item.label.replace("_", "__")));
+        properties.insert(String::from("enabled"),
+            variant(item.action.is_some() || !item.children.is_empty()));
+        if let Some(checked) = item.checked {
+            properties.insert(String::from("toggle-type"), variant(String::from("checkmark")));
+            properties.insert(String::from("toggle-state"), variant(if checked { 1i32 } else { 0i32 }));
+        }
+    }
+    properties
+}
+
+// Keeps only the properties the host asked for, an empty list means all
+fn filter_properties(mut properties: HashMap<String, Variant<Box<RefArg>>>,
+    names: &[&str]) -> HashMap<String, Variant<Box<RefArg>>> {
+    if !names.is_empty() {
+        properties.retain(|name, _| names.contains(&name.as_str()));
+    }
+    properties
+}
+
+fn build_layout(nodes: &HashMap<i32, Node>, id: i32, depth: i32, names: &[&str]) -> Layout {
+    let node = &nodes[&id];
+    // A negative depth means the whole subtree
+    let children = if depth == 0 {
+        Vec::new()
+    } else {
+        node.children.iter()
+            .map(|child| variant(build_layout(nodes, *child, depth - 1, names)))
+            .collect()
+    };
+    (id, filter_properties(get_node_properties(node), names), children)
+}
+
+pub fn get_layout(items: &[MenuItem], ids: &MenuIds, parent_id: i32, depth: i32,
+    names: &[&str]) -> Option<Layout> {
+    let nodes = get_nodes(items, ids);
+    if !nodes.contains_key(&parent_id) {
+        return None
+    }
+    Some(build_layout(&nodes, parent_id, depth, names))
+}
+
+pub fn get_properties(items: &[MenuItem], ids: &MenuIds, id: i32,
+    names: &[&str]) -> Option<HashMap<String, Variant<Box<RefArg>>>> {
+    get_nodes(items, ids).get(&id)
+        .map(|node| filter_properties(get_node_properties(node), names))
+}
+
+// Action of the entry with the given id, None when the entry is no longer in
+// the menu
+pub fn get_action(items: &[MenuItem], ids: &MenuIds, id: i32) -> Option<String> {
+    get_nodes(items, ids).get(&id)
+        .and_then(|node| node.item)
+        .and_then(|item| item.action.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dbus::arg;
+
+    fn get_id(layout: &Layout, label: &str) -> Option<i32> {
+        let label = label.replace("_", "__");
+        if layout.1.get("label").and_then(|value| value.0.as_str()) == Some(&label) {
+            return Some(layout.0)
+        }
+        layout.2.iter()
+            .filter_map(|child| arg::cast::<Layout>(&*child.0))
+            .filter_map(|child| get_id(child, &label))
+            .next()
+    }
+
+    #[test]
+    fn clicks_follow_entries_across_rebuilds() {
+        let first = vec![
+            MenuItem::with_action(String::from("Home"), String::from("wifi-connect dev ap1")),
+            MenuItem::with_action(String::from("Cafe"), String::from("wifi-connect dev ap2"))
+        ];
+        let mut ids = MenuIds::new();
+        ids.update(&first);
+        let layout = get_layout(&first, &ids, 0, -1, &[]).unwrap();
+        let cafe = get_id(&layout, "Cafe").unwrap();
+        let home = get_id(&layout, "Home").unwrap();
+        // A scan reorders the networks and drops one before the click
+        let second = vec![
+            MenuItem::with_action(String::from("Office"), String::from("wifi-connect dev ap3")),
+            MenuItem::with_action(String::from("Cafe"), String::from("wifi-connect dev ap2"))
+        ];
+        ids.update(&second);
+        assert_eq!(get_action(&second, &ids, cafe), Some(String::from("wifi-connect dev ap2")));
+        assert_eq!(get_action(&second, &ids, home), None);
+        let layout = get_layout(&second, &ids, 0, -1, &[]).unwrap();
+        assert!(get_id(&layout, "Office").map_or(false, |office| office != home && office != cafe));
+    }
+
+    #[test]
+    fn repeated_entries_get_their_own_ids() {
+        let items = vec![
+            MenuItem::separator(),
+            MenuItem::submenu(String::from("Networks"), vec![MenuItem::new(String::from("None"))]),
+            MenuItem::separator()
+        ];
+        let mut ids = MenuIds::new();
+        ids.update(&items);
+        let layout = get_layout(&items, &ids, 0, -1, &[]).unwrap();
+        let mut children: Vec<i32> = layout.2.iter()
+            .filter_map(|child| arg::cast::<Layout>(&*child.0).map(|child| child.0))
+            .collect();
+        children.sort();
+        children.dedup();
+        assert_eq!(children.len(), 3);
+    }
+}
@@ -1,39 +1,294 @@
 use dbus::Connection;
+use dbus::ConnPath;
 use dbus::BusType;
-use dbus::arg::RefArg;
 use dbus::Error;
+use dbus::Message;
+use dbus::SignalArgs;
+use dbus::arg::{RefArg, Variant};
 
 use network_manager::OrgFreedesktopDBusProperties;
+use network_manager::OrgFreedesktopNetworkManager;
+use network_manager::OrgFreedesktopNetworkManagerAccessPoint;
+use network_manager::OrgFreedesktopNetworkManagerConnectionActive;
+use network_manager::OrgFreedesktopNetworkManagerDevice;
+use network_manager::OrgFreedesktopNetworkManagerDeviceWireless;
+use network_manager::OrgFreedesktopDBusPropertiesPropertiesChanged;
+use network_manager::OrgFreedesktopNetworkManagerPropertiesChanged;
+use network_manager::OrgFreedesktopNetworkManagerStateChanged;
+use network_manager::OrgFreedesktopNetworkManagerSettingsNewConnection;
+use network_manager::OrgFreedesktopNetworkManagerSettingsConnectionRemoved;
+use network_manager::OrgFreedesktopNetworkManagerSettingsConnectionUpdated;
+use properties::PropertyCache;
+use radio;
+use radio::Radios;
+use saved_connection;
+use saved_connection::SavedConnection;
+use throughput;
+use throughput::Throughput;
+use wifi;
+use wifi::WifiNetwork;
+use active_connection;
+use active_connection::ActiveConnection;
+use vpn;
+use config::Config;
+use connection_details::ConnectionDetails;
+use data_usage;
+use data_usage::DataUsage;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::process::Command;
 use std::rc::Rc;
-use tokio_core::reactor::Core;
-use futures::{Stream};
+use std::thread;
+use std::time::Duration;
+use tokio_core::reactor::{Core, Handle, Interval};
+use futures::{Future, Stream};
+use futures::unsync::mpsc;
+use futures::unsync::mpsc::UnboundedSender;
+use futures::unsync::oneshot;
 use dbus_tokio::AConnection;
 
+use menu::MenuItem;
+use status::{Action, ActionHandler, StatusUpdate};
 use std::sync::mpsc::{Sender};
 
 // NMState:
 // https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMState
-const NM_STATE_UNKNOWN: i64 = 0;
-const NM_STATE_STATE_ASLEEP: i64 = 10;
-const NM_STATE_DISCONNECTED: i64 = 20;
-const NM_STATE_DISCONNECTING: i64 = 30;
-const NM_STATE_CONNECTING: i64 = 40;
-const NM_STATE_CONNECTED_LOCAL: i64 = 50;
-const NM_STATE_CONNECTED_SITE: i64 = 60;
-const NM_STATE_CONNECTED_GLOBAL: i64 = 70;
+pub const NM_STATE_UNKNOWN: i64 = 0;
+pub const NM_STATE_STATE_ASLEEP: i64 = 10;
+pub const NM_STATE_DISCONNECTED: i64 = 20;
+pub const NM_STATE_DISCONNECTING: i64 = 30;
+pub const NM_STATE_CONNECTING: i64 = 40;
+pub const NM_STATE_CONNECTED_LOCAL: i64 = 50;
+pub const NM_STATE_CONNECTED_SITE: i64 = 60;
+pub const NM_STATE_CONNECTED_GLOBAL: i64 = 70;
+
+// NMConnectivityState:
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMConnectivityState
+const NM_CONNECTIVITY_UNKNOWN: i64 = 0;
+const NM_CONNECTIVITY_NONE: i64 = 1;
+const NM_CONNECTIVITY_PORTAL: i64 = 2;
+const NM_CONNECTIVITY_LIMITED: i64 = 3;
+const NM_CONNECTIVITY_FULL: i64 = 4;
+
+// NMMetered:
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMMetered
+const NM_METERED_UNKNOWN: i64 = 0;
+const NM_METERED_YES: i64 = 1;
+const NM_METERED_NO: i64 = 2;
+const NM_METERED_GUESS_YES: i64 = 3;
+const NM_METERED_GUESS_NO: i64 = 4;
+
+// Opened in the browser to get redirected to a captive portal's sign-in page,
+// the same page GNOME Shell opens
+const DEFAULT_PORTAL_URL: &'static str = "http://nmcheck.gnome.org/";
+
+// Menu actions of the network item
+const ACTION_OPEN_PORTAL: &'static str = "open-portal";
+const ACTION_CHECK_CONNECTIVITY: &'static str = "check-connectivity";
+
+// How a connection type is shown
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectionKind {
+    Wireless,
+    Wired,
+    // Mobile broadband
+    Cellular,
+    // Tethering through a phone over Bluetooth
+    Bluetooth,
+    Vpn,
+    Loopback
+}
+
+// NetworkManager connection types, from the `type` setting of connection
+// profiles. Types missing from the table are shown as wired.
+// https://developer.gnome.org/NetworkManager/stable/nm-settings.html
+const CONNECTION_TYPES: [(&'static str, ConnectionKind); 27] = [
+    ("802-11-wireless", ConnectionKind::Wireless),
+    ("802-11-olpc-mesh", ConnectionKind::Wireless),
+    ("wifi-p2p", ConnectionKind::Wireless),
+    ("wimax", ConnectionKind::Wireless),
+    ("802-3-ethernet", ConnectionKind::Wired),
+    ("bridge", ConnectionKind::Wired),
+    ("bond", ConnectionKind::Wired),
+    ("team", ConnectionKind::Wired),
+    ("vlan", ConnectionKind::Wired),
+    ("macvlan", ConnectionKind::Wired),
+    ("vxlan", ConnectionKind::Wired),
+    ("veth", ConnectionKind::Wired),
+    ("infiniband", ConnectionKind::Wired),
+    ("pppoe", ConnectionKind::Wired),
+    ("adsl", ConnectionKind::Wired),
+    ("ovs-bridge", ConnectionKind::Wired),
+    ("ovs-port", ConnectionKind::Wired),
+    ("ovs-interface", ConnectionKind::Wired),
+    ("gsm", ConnectionKind::Cellular),
+    ("cdma", ConnectionKind::Cellular),
+    ("bluetooth", ConnectionKind::Bluetooth),
+    ("vpn", ConnectionKind::Vpn),
+    ("wireguard", ConnectionKind::Vpn),
+    ("tun", ConnectionKind::Vpn),
+    ("ip-tunnel", ConnectionKind::Vpn),
+    ("macsec", ConnectionKind::Vpn),
+    ("loopback", ConnectionKind::Loopback)
+];
+
+fn get_connection_kind(connection_type: &str) -> ConnectionKind {
+    match CONNECTION_TYPES.iter().find(|&&(name, _)| name == connection_type) {
+        Some(&(_, kind)) => kind,
+        None => {
+            info!("Unknown connection type {}, shown as wired", connection_type);
+            ConnectionKind::Wired
+        }
+    }
+}
+
+// Same thresholds as GNOME Shell
+fn get_signal_icon(strength: u8) -> String {
+    let signal = match strength {
+        0 ... 19 => "none",
+        20 ... 39 => "weak",
+        40 ... 49 => "ok",
+        50 ... 79 => "good",
+        _ => "excellent"
+    };
+    format!("network-wireless-signal-{}-symbolic", signal)
+}
+
+fn get_wireless_icon(state: i64, strength: Option<u8>) -> String {
+    match state {
+        NM_STATE_CONNECTED_GLOBAL => match strength {
+            Some(strength) => get_signal_icon(strength),
+            None => String::from("network-wireless-signal-excellent-symbolic")
+        },
+        NM_STATE_CONNECTED_SITE => String::from("network-wireless-no-route-symbolic"),
+        NM_STATE_CONNECTING => String::from("network-wireless-acquiring-symbolic"),
+        NM_STATE_CONNECTED_LOCAL | NM_STATE_DISCONNECTING | NM_STATE_DISCONNECTED |
+        NM_STATE_STATE_ASLEEP | NM_STATE_UNKNOWN | _ => {
+            String::from("network-wireless-disconnected-symbolic")
+        }
+    }
+}
+
+fn get_wired_icon(state: i64) -> String {
+    match state {
+        NM_STATE_CONNECTED_GLOBAL => String::from("network-transmit-receive-symbolic"),
+        NM_STATE_CONNECTED_SITE => String::from("network-wired-no-route-symbolic"),
+        NM_STATE_CONNECTING => String::from("network-wired-acquiring-symbolic"),
+        NM_STATE_CONNECTED_LOCAL | NM_STATE_DISCONNECTING | NM_STATE_DISCONNECTED => {
+            String::from("network-wired-disconnected-symbolic")
+        },
+        NM_STATE_STATE_ASLEEP | NM_STATE_UNKNOWN | _ => {
+            String::from("network-wired-offline-symbolic")
+        }
+    }
+}
+
+fn get_cellular_icon(state: i64) -> String {
+    match state {
+        NM_STATE_CONNECTED_GLOBAL => String::from("network-cellular-connected-symbolic"),
+        NM_STATE_CONNECTED_SITE => String::from("network-cellular-no-route-symbolic"),
+        NM_STATE_CONNECTING => String::from("network-cellular-acquiring-symbolic"),
+        NM_STATE_CONNECTED_LOCAL | NM_STATE_DISCONNECTING | NM_STATE_DISCONNECTED |
+        NM_STATE_STATE_ASLEEP | NM_STATE_UNKNOWN | _ => {
+            String::from("network-cellular-offline-symbolic")
+        }
+    }
+}
+
+// Icons of the other kinds only differ when connected, otherwise they
+// fall back to the wired icons
+pub fn get_icon(kind: ConnectionKind, state: i64, strength: Option<u8>) -> String {
+    match (kind, state) {
+        (ConnectionKind::Wireless, _) => get_wireless_icon(state, strength),
+        (ConnectionKind::Cellular, _) => get_cellular_icon(state),
+        (ConnectionKind::Bluetooth, NM_STATE_CONNECTED_GLOBAL) => {
+            String::from("bluetooth-active-symbolic")
+        },
+        (ConnectionKind::Vpn, NM_STATE_CONNECTED_GLOBAL) => String::from("network-vpn-symbolic"),
+        (ConnectionKind::Vpn, NM_STATE_CONNECTING) => String::from("network-vpn-acquiring-symbolic"),
+        // Only the loopback interface is up, there is no network
+        (ConnectionKind::Loopback, _) => String::from("network-wired-disconnected-symbolic"),
+        _ => get_wired_icon(state)
+    }
+}
+
+// NMDeviceType
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMDeviceType
+pub const NM_DEVICE_TYPE_WIFI: u32 = 2;
+pub const NM_DEVICE_TYPE_MODEM: u32 = 8;
+
+// NetworkManager properties that affect the network status
+const RELEVANT_PROPERTIES: [&'static str; 7] = [
+    "State", "PrimaryConnection", "PrimaryConnectionType", "ActiveConnections",
+    "Connectivity", "ConnectivityCheckAvailable", "Metered"
+];
+
+// Device of the primary connection and, for Wi-Fi, the access point it is
+// associated with
+#[derive(Debug, Default)]
+struct PrimaryDevice {
+    path: String,
+    access_point: Option<String>,
+    strength: Option<u8>
+}
 
 pub struct NetworkMonitor {
-    conn: Rc<Connection>
+    conn: Rc<Connection>,
+    properties: RefCell<PropertyCache>,
+    active_connections: RefCell<Vec<ActiveConnection>>,
+    primary: RefCell<Option<PrimaryDevice>>,
+    details: RefCell<Option<ConnectionDetails>>,
+    // IP4Config and IP6Config objects of the base connection, their
+    // addresses change without the connection changing
+    ip_configs: RefCell<Vec<String>>,
+    // Objects whose PropertiesChanged signals are matched
+    watched: RefCell<Vec<String>>,
+    show_address: bool,
+    // Run with the metered state whenever it changes
+    metered_hook: Option<String>,
+    metered: RefCell<Option<Metered>>,
+    has_modem: RefCell<bool>,
+    wireless_devices: RefCell<Vec<String>>,
+    wifi_networks: RefCell<Vec<WifiNetwork>>,
+    saved_connections: RefCell<Vec<SavedConnection>>,
+    throughput: Option<Throughput>,
+    data_usage: Option<DataUsage>,
+    // Interval of the running sampling timer, which stops when its sender
+    // is dropped
+    ticks: RefCell<Option<(Duration, oneshot::Sender<()>)>>
+}
+
+// What wakes the network monitor up
+enum Event {
+    Signal(Message),
+    // Time to sample the throughput and data usage
+    Tick
 }
 
 
 impl NetworkMonitor {
-    pub fn new() -> Result<NetworkMonitor, Error> {
+    pub fn new(config: &Config) -> Result<NetworkMonitor, Error> {
         match Connection::get_private(BusType::System) {
             Ok(c) => {
                 Ok(NetworkMonitor {
-                    conn: Rc::new(c)
+                    conn: Rc::new(c),
+                    properties: RefCell::new(PropertyCache::new()),
+                    active_connections: RefCell::new(Vec::new()),
+                    primary: RefCell::new(None),
+                    details: RefCell::new(None),
+                    ip_configs: RefCell::new(Vec::new()),
+                    watched: RefCell::new(Vec::new()),
+                    show_address: config.get_bool("network", "show_address").unwrap_or(false),
+                    metered_hook: config.get_str("network", "metered_hook").map(String::from),
+                    metered: RefCell::new(None),
+                    has_modem: RefCell::new(false),
+                    wireless_devices: RefCell::new(Vec::new()),
+                    wifi_networks: RefCell::new(Vec::new()),
+                    saved_connections: RefCell::new(Vec::new()),
+                    throughput: Throughput::new(config),
+                    data_usage: DataUsage::new(config),
+                    ticks: RefCell::new(None)
                 })
             },
             Err(err) => {
@@ -42,100 +297,636 @@ impl NetworkMonitor {
         }
     }
 
-    fn get_state(&self) -> Option<i64> {
-        info!("Getting connection state");
+    // Loads all NetworkManager properties into the cache
+    fn load_properties(&self) {
         let c_path = self.conn.with_path(
-            "org.freedesktop.NetworkManager", 
-            "/org/freedesktop/NetworkManager", 
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
             5000);
-        if let Ok(variant) = c_path.get("org.freedesktop.NetworkManager",
-        "State") {
-            return variant.as_i64();
-        } else {
-            return None
+        match c_path.get_all("org.freedesktop.NetworkManager") {
+            Ok(all) => {
+                self.properties.borrow_mut().update(all, &[]);
+            },
+            Err(err) => error!("Could not get network properties: {:?}", err)
         }
+        self.load_devices();
+        self.load_saved_connections();
+        self.load_primary();
     }
 
-    fn get_primary_connection_type(&self) -> Option<String> {
-        info!("Getting primary connection type");
+    // Finds the modems, for the radio menu, and the wireless devices, whose
+    // networks are listed in the menu
+    fn load_devices(&self) {
         let c_path = self.conn.with_path(
             "org.freedesktop.NetworkManager",
             "/org/freedesktop/NetworkManager",
             5000);
-        if let Ok(variant) = c_path.get("org.freedesktop.NetworkManager", 
-            "PrimaryConnectionType") {
-            if let Some(variant_str) = variant.as_str() {
-                return Some(String::from(variant_str));
-            } else {
-                return None
+        let devices = match OrgFreedesktopNetworkManager::get_devices(&c_path) {
+            Ok(devices) => devices,
+            Err(err) => {
+                error!("Could not get network devices: {:?}", err);
+                return
             }
-        } else {
-            error!("Could not get network connection type");
-            return None
+        };
+        let device_types: Vec<(String, u32)> = devices.iter().filter_map(|device| {
+            let c_path = self.conn.with_path(
+                "org.freedesktop.NetworkManager", device.clone(), 5000);
+            c_path.get_device_type().ok().map(|device_type| (device.to_string(), device_type))
+        }).collect();
+        *self.has_modem.borrow_mut() = device_types.iter()
+            .any(|&(_, device_type)| device_type == NM_DEVICE_TYPE_MODEM);
+        *self.wireless_devices.borrow_mut() = device_types.into_iter()
+            .filter(|&(_, device_type)| device_type == NM_DEVICE_TYPE_WIFI)
+            .map(|(device, _)| device)
+            .collect();
+        self.load_wifi_networks();
+    }
+
+    // Returns whether the saved connections changed
+    fn load_saved_connections(&self) -> bool {
+        let connections = saved_connection::load_saved_connections(&self.conn);
+        let changed = *self.saved_connections.borrow() != connections;
+        *self.saved_connections.borrow_mut() = connections;
+        changed
+    }
+
+    fn load_wifi_networks(&self) {
+        *self.wifi_networks.borrow_mut() =
+            wifi::load_networks(&self.conn, &self.wireless_devices.borrow());
+    }
+
+    // Follows the base connection to its device and access point
+    fn load_primary(&self) {
+        *self.active_connections.borrow_mut() =
+            active_connection::load_active_connections(&self.conn);
+        let primary = self.get_base_connection()
+            .and_then(|connection| self.get_primary_device(&connection.path));
+        *self.primary.borrow_mut() = primary;
+        self.load_details();
+        self.watch_primary();
+    }
+
+    fn load_details(&self) {
+        let base = self.get_base_connection();
+        *self.details.borrow_mut() = base.as_ref()
+            .map(|connection| ConnectionDetails::load(&self.conn, &connection.path));
+        *self.ip_configs.borrow_mut() = base.map(|connection| {
+            let c_path = self.conn.with_path(
+                "org.freedesktop.NetworkManager", connection.path, 5000);
+            vec![OrgFreedesktopNetworkManagerConnectionActive::get_ip4_config(&c_path),
+                OrgFreedesktopNetworkManagerConnectionActive::get_ip6_config(&c_path)]
+                .into_iter()
+                .filter_map(|config| config.ok())
+                .map(|config| config.to_string())
+                .filter(|config| config != "/")
+                .collect()
+        }).unwrap_or(Vec::new());
+    }
+
+    // The primary connection, or the connection a VPN goes through when the
+    // VPN is primary
+    fn get_base_connection(&self) -> Option<ActiveConnection> {
+        let primary = match self.get_primary_connection() {
+            Some(primary) => primary,
+            None => return None
+        };
+        let active_connections = self.active_connections.borrow();
+        let vpn = match active_connections.iter().find(|connection| connection.path == primary) {
+            Some(connection) if connection.vpn => connection,
+            Some(connection) => return Some(connection.clone()),
+            None => return None
+        };
+        let base: Vec<&ActiveConnection> = active_connections.iter()
+            .filter(|connection| !connection.vpn && connection.is_activated())
+            .collect();
+        // The VPN's parent connection, then the connection on the VPN's
+        // device, which NetworkManager sets for plugin VPNs
+        let parent = vpn.parent.as_ref().and_then(|parent| {
+            base.iter().find(|connection| connection.path == *parent)
+        }).or_else(|| base.iter().find(|connection| {
+            connection.devices.iter().any(|device| vpn.devices.contains(device))
+        }));
+        match parent {
+            Some(connection) => Some((*connection).clone()),
+            // WireGuard has a device of its own, the base connection is
+            // only known when there is a single one
+            None if base.len() == 1 => Some(base[0].clone()),
+            None => None
         }
     }
 
-    fn update_wifi_status(&self, state: i64) -> String {
-        match state {
-            NM_STATE_CONNECTED_GLOBAL => {
-                return String::from("network-wireless-signal-excellent-symbolic");
-            },
-            NM_STATE_CONNECTED_SITE => {
-                return String::from("network-wireless-no-route-symbolic");
-            },
-            NM_STATE_CONNECTED_LOCAL => {
-                return String::from("network-wireless-disconnected-symbolic");
-            },
-            NM_STATE_CONNECTING => {
-                return String::from("network-wireless-acquiring-symbolic");
+    fn get_primary_device(&self, connection: &str) -> Option<PrimaryDevice> {
+        let c_path = self.conn.with_path(
+            "org.freedesktop.NetworkManager", connection, 5000);
+        let path = match OrgFreedesktopNetworkManagerConnectionActive::get_devices(&c_path) {
+            Ok(devices) => match devices.first() {
+                Some(device) => device.to_string(),
+                // VPNs have no device of their own
+                None => return None
             },
-            NM_STATE_DISCONNECTING | NM_STATE_DISCONNECTED => {
-                return String::from("network-wireless-disconnected-symbolic");
+            Err(err) => {
+                error!("Could not get primary connection devices: {:?}", err);
+                return None
+            }
+        };
+        let mut primary = PrimaryDevice {
+            path: path,
+            access_point: None,
+            strength: None
+        };
+        self.load_access_point(&mut primary);
+        Some(primary)
+    }
+
+    fn load_access_point(&self, primary: &mut PrimaryDevice) {
+        let c_path = self.conn.with_path(
+            "org.freedesktop.NetworkManager", primary.path.clone(), 5000);
+        primary.access_point = match c_path.get_device_type() {
+            Ok(NM_DEVICE_TYPE_WIFI) => match c_path.get_active_access_point() {
+                Ok(ref access_point) if &**access_point != "/" => Some(access_point.to_string()),
+                _ => None
             },
-            NM_STATE_STATE_ASLEEP | NM_STATE_UNKNOWN | _ => {
-                return String::from("network-wireless-disconnected-symbolic");
+            _ => None
+        };
+        primary.strength = primary.access_point.as_ref().and_then(|access_point| {
+            let c_path = self.conn.with_path(
+                "org.freedesktop.NetworkManager", access_point.clone(), 5000);
+            c_path.get_strength().ok()
+        });
+    }
+
+    // Matches PropertiesChanged for the active connections, the wireless
+    // devices, the primary device and access point and the base connection's
+    // IP configs, dropping the matches of the previous ones
+    fn watch_primary(&self) {
+        let mut paths: Vec<String> = self.active_connections.borrow().iter()
+            .map(|connection| connection.path.clone())
+            .collect();
+        // Their access points change with scans
+        for device in self.wireless_devices.borrow().iter() {
+            if !paths.contains(device) {
+                paths.push(device.clone());
+            }
+        }
+        if let Some(ref primary) = *self.primary.borrow() {
+            if !paths.contains(&primary.path) {
+                paths.push(primary.path.clone());
+            }
+            if let Some(ref access_point) = primary.access_point {
+                paths.push(access_point.clone());
+            }
+        }
+        paths.extend(self.ip_configs.borrow().iter().cloned());
+        let mut watched = self.watched.borrow_mut();
+        for path in watched.iter().filter(|path| !paths.contains(path)) {
+            if let Err(err) = self.conn.remove_match(&get_properties_match(path)) {
+                error!("Network monitor could not remove dbus match: {:?}", err);
             }
         }
+        for path in paths.iter().filter(|path| !watched.contains(path)) {
+            if let Err(err) = self.conn.add_match(&get_properties_match(path)) {
+                error!("Network monitor could not add dbus match: {:?}", err);
+            }
+        }
+        *watched = paths;
     }
 
-    fn update_wired_status(&self, state: i64) -> String {
-        match state {
-            NM_STATE_CONNECTED_GLOBAL => {
-                return String::from("network-transmit-receive-symbolic");
-            },
-            NM_STATE_CONNECTED_SITE => {
-                return String::from("network-wired-no-route-symbolic");
-            },
-            NM_STATE_CONNECTED_LOCAL => {
-                return String::from("network-wired-disconnected-symbolic");
-            },
-            NM_STATE_CONNECTING => {
-                return String::from("network-wired-acquiring-symbolic");
-            },
-            NM_STATE_DISCONNECTING | NM_STATE_DISCONNECTED => {
-                return String::from("network-wired-disconnected-symbolic");
-            },
-            NM_STATE_STATE_ASLEEP | NM_STATE_UNKNOWN | _ => {
-                return String::from("network-wired-offline-symbolic");
+    // Updates the primary connection from a PropertiesChanged signal on the
+    // connection, its device or access point, returns whether the status
+    // needs to be recomputed
+    fn update_primary(&self, path: &str,
+        changed: &OrgFreedesktopDBusPropertiesPropertiesChanged) -> bool {
+        let is_active_connection = self.active_connections.borrow().iter()
+            .any(|connection| connection.path == path);
+        if is_active_connection {
+            // Addresses are only known once the connection is activated
+            let properties = ["Id", "Ip4Config", "Ip6Config", "State"];
+            if properties.iter().any(|name| changed.changed_properties.contains_key(*name)) {
+                let active_connections = self.active_connections.borrow().clone();
+                let details = self.details.borrow().clone();
+                self.load_primary();
+                return *self.active_connections.borrow() != active_connections ||
+                    *self.details.borrow() != details
+            }
+            return false
+        }
+        if self.ip_configs.borrow().iter().any(|config| config == path) {
+            let details = self.details.borrow().clone();
+            self.load_details();
+            return *self.details.borrow() != details
+        }
+        let mut reload_access_point = false;
+        let mut needs_update = false;
+        if self.wireless_devices.borrow().iter().any(|device| device == path) {
+            let properties = ["AccessPoints", "ActiveAccessPoint", "LastScan"];
+            if properties.iter().any(|name| changed.changed_properties.contains_key(*name)) {
+                let networks = self.wifi_networks.borrow().clone();
+                self.load_wifi_networks();
+                needs_update = *self.wifi_networks.borrow() != networks;
+            }
+        }
+        if let Some(ref mut primary) = *self.primary.borrow_mut() {
+            if path == primary.path {
+                if changed.changed_properties.contains_key("ActiveAccessPoint") {
+                    self.load_access_point(primary);
+                    reload_access_point = true;
+                    needs_update = true;
+                }
+            } else if primary.access_point.as_ref().map(|ap| ap == path).unwrap_or(false) {
+                if let Some(strength) = changed.changed_properties.get("Strength")
+                    .and_then(|strength| strength.0.as_i64()) {
+                    needs_update = primary.strength != Some(strength as u8);
+                    primary.strength = Some(strength as u8);
+                }
+            }
+        }
+        if reload_access_point {
+            self.load_details();
+            self.watch_primary();
+        }
+        needs_update
+    }
+
+    // Updates the cache from a StateChanged or PropertiesChanged signal,
+    // returns whether the network status needs to be recomputed. 
+    // NetworkManager sends both its own PropertiesChanged signal and the
+    // standard one, the cache makes the second one cheap.
+    fn update_properties(&self, m: &Message) -> bool {
+        if let Some(state_changed) = OrgFreedesktopNetworkManagerStateChanged::from_message(m) {
+            let state = Variant(Box::new(state_changed.state) as Box<RefArg>);
+            let changed_state = self.get_state() != Some(state_changed.state as i64);
+            self.properties.borrow_mut().insert("State", state);
+            return changed_state
+        }
+        if OrgFreedesktopNetworkManagerSettingsNewConnection::from_message(m).is_some() ||
+            OrgFreedesktopNetworkManagerSettingsConnectionRemoved::from_message(m).is_some() ||
+            OrgFreedesktopNetworkManagerSettingsConnectionUpdated::from_message(m).is_some() {
+            return self.load_saved_connections()
+        }
+        if let Some(changed) = OrgFreedesktopDBusPropertiesPropertiesChanged::from_message(m) {
+            let path = m.path().map(|path| path.to_string()).unwrap_or(String::new());
+            if path != "/org/freedesktop/NetworkManager" {
+                return self.update_primary(&path, &changed)
+            }
+            if changed.interface_name != "org.freedesktop.NetworkManager" {
+                return false
+            }
+            return self.update_global(changed.changed_properties)
+        }
+        if let Some(changed) = OrgFreedesktopNetworkManagerPropertiesChanged::from_message(m) {
+            return self.update_global(changed.properties)
+        }
+        return false
+    }
+
+    fn update_global(&self, changed: HashMap<String, Variant<Box<RefArg>>>) -> bool {
+        let reload = changed.contains_key("PrimaryConnection") ||
+            changed.contains_key("ActiveConnections");
+        let reload_devices = changed.contains_key("Devices");
+        let mut relevant = RELEVANT_PROPERTIES.to_vec();
+        relevant.extend(radio::RADIO_PROPERTIES.iter());
+        let needs_update = self.properties.borrow_mut().update(changed, &relevant);
+        if reload_devices {
+            let has_modem = *self.has_modem.borrow();
+            let wireless_devices = self.wireless_devices.borrow().clone();
+            self.load_devices();
+            self.watch_primary();
+            if *self.has_modem.borrow() != has_modem ||
+                *self.wireless_devices.borrow() != wireless_devices {
+                return true
+            }
+        }
+        if reload {
+            self.load_primary();
+        }
+        needs_update
+    }
+
+    fn get_state(&self) -> Option<i64> {
+        self.properties.borrow().get_i64("State")
+    }
+
+    // None when there is no primary connection
+    fn get_primary_connection(&self) -> Option<String> {
+        match self.properties.borrow().get_str("PrimaryConnection") {
+            Some(path) if path != "/" => Some(String::from(path)),
+            _ => None
+        }
+    }
+
+    fn get_connectivity(&self) -> i64 {
+        self.properties.borrow().get_i64("Connectivity").unwrap_or(NM_CONNECTIVITY_UNKNOWN)
+    }
+
+    fn get_radios(&self) -> Radios {
+        Radios::from_properties(&self.properties.borrow(), *self.has_modem.borrow())
+    }
+
+    fn get_metered(&self) -> Option<Metered> {
+        get_metered(self.properties.borrow().get_i64("Metered").unwrap_or(NM_METERED_UNKNOWN))
+    }
+
+    fn get_strength(&self) -> Option<u8> {
+        self.primary.borrow().as_ref().and_then(|primary| primary.strength)
+    }
+
+    // Type of the base connection, so that a VPN doesn't hide whether it
+    // goes over Wi-Fi or ethernet
+    fn get_primary_connection_type(&self) -> Option<String> {
+        if let Some(connection) = self.get_base_connection() {
+            return Some(connection.connection_type)
+        }
+        match self.properties.borrow().get_str("PrimaryConnectionType") {
+            Some(connection_type) => Some(String::from(connection_type)),
+            None => {
+                error!("Could not get network connection type");
+                None
+            }
+        }
+    }
+
+    fn update_status(&self) -> StatusUpdate {
+        let mut update = self.update_connection_status();
+        if let Some(ref details) = *self.details.borrow() {
+            let mut text = vec![details.name.clone()];
+            if self.show_address {
+                text.extend(details.get_address());
+            }
+            if !update.text.is_empty() {
+                text.push(update.text.clone());
             }
+            update.text = text.into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<String>>()
+                .join(" ");
+            let mut lines = details.lines();
+            if !update.tooltip.is_empty() {
+                lines.push(update.tooltip.clone());
+            }
+            lines.extend(self.get_data_usage_description());
+            lines.extend(vpn::get_vpn_lines(&self.active_connections.borrow()));
+            update.tooltip = lines.join("\n");
+            update.menu = details.menu_items();
+        }
+        update.menu.extend(self.get_connectivity_menu_items());
+        if !update.menu.is_empty() {
+            update.menu.push(MenuItem::separator());
+        }
+        update.menu.extend(wifi::menu_item(&self.wifi_networks.borrow(),
+            !self.wireless_devices.borrow().is_empty()));
+        update.menu.extend(saved_connection::menu_item(&self.saved_connections.borrow(),
+            &self.active_connections.borrow()));
+        update.menu.extend(self.get_radios().menu_items());
+        update
+    }
+
+    fn get_connectivity_menu_items(&self) -> Vec<MenuItem> {
+        let mut items = Vec::new();
+        if self.get_connectivity() == NM_CONNECTIVITY_PORTAL {
+            items.push(MenuItem::with_action(String::from("Sign in to network"),
+                String::from(ACTION_OPEN_PORTAL)));
+        }
+        if self.properties.borrow().get_bool("ConnectivityCheckAvailable").unwrap_or(false) {
+            items.push(MenuItem::with_action(String::from("Check connectivity"),
+                String::from(ACTION_CHECK_CONNECTIVITY)));
         }
+        if !items.is_empty() {
+            items.insert(0, MenuItem::separator());
+        }
+        items
     }
 
-    fn update_status(&self) -> String {        
+    fn update_connection_status(&self) -> StatusUpdate {        
+        let radios = self.get_radios();
         if let Some(state) = self.get_state() {
+            // Wired connections keep working in airplane mode
+            if radios.is_airplane_mode() && state < NM_STATE_CONNECTED_LOCAL {
+                let mut update = StatusUpdate::new(String::from("airplane-mode-symbolic"));
+                update.text = String::from("airplane mode");
+                update.tooltip = radios.description().unwrap_or(String::new());
+                return update
+            }
             if let Some(connection_type) = self.get_primary_connection_type() {
                 info!("Connection type: {:?}", connection_type);
-                match connection_type.as_ref() {
-                    "802-11-wireless" => return self.update_wifi_status(state),
-                    "802-3-ethernet" => return self.update_wired_status(state),
-                    _  => return String::from("network-wired-no-route-symbolic")
+                let kind = get_connection_kind(&connection_type);
+                let connectivity = self.get_connectivity();
+                let mut update = StatusUpdate::new(
+                    get_icon(kind, get_connectivity_state(state, connectivity), self.get_strength()));
+                let mut text = Vec::new();
+                let mut tooltip = Vec::new();
+                if kind == ConnectionKind::Wireless {
+                    if let Some(strength) = self.get_strength() {
+                        text.push(format!("{}%", strength));
+                        tooltip.push(format!("Signal strength: {}%", strength));
+                    }
+                }
+                if state >= NM_STATE_CONNECTED_LOCAL {
+                    if let Some(metered) = self.get_metered() {
+                        if metered.metered {
+                            text.push(String::from("metered"));
+                        }
+                        tooltip.extend(metered.description());
+                    }
+                    if let Some(description) = get_connectivity_description(connectivity) {
+                        if connectivity == NM_CONNECTIVITY_PORTAL {
+                            text.push(String::from("sign in"));
+                        }
+                        tooltip.push(description);
+                    }
                 }
+                tooltip.extend(radios.description());
+                update.text = text.join(" ");
+                update.tooltip = tooltip.join("\n");
+                return update
             }
         }
-        return String::from("network-wired-acquiring-symbolic")
+        return StatusUpdate::new(String::from("network-wired-acquiring-symbolic"))
     }
 
-    pub fn run(&self, out_chan: Sender<String>) {
+    fn send_status(&self, out_chan: &Sender<StatusUpdate>, vpn_chan: &Sender<StatusUpdate>) {
+        let update = self.update_status();
+        let icon = update.icon_name.clone();
+        match out_chan.send(update) {
+            Ok(_) => info!("Sent network icon: {}", icon),
+            Err(err) => error!("Could not send network icon: {} - {}", icon, err)
+        }
+        let update = vpn::get_vpn_status(&self.active_connections.borrow());
+        if let Err(err) = vpn_chan.send(update) {
+            error!("Could not send VPN status: {}", err);
+        }
+        self.run_metered_hook();
+    }
+
+    // Tells the metered hook about the first known metered state and every
+    // change after it
+    fn run_metered_hook(&self) {
+        let hook = match self.metered_hook {
+            Some(ref hook) => hook,
+            None => return
+        };
+        let metered = match self.get_metered() {
+            Some(metered) => metered,
+            None => return
+        };
+        if *self.metered.borrow() == Some(metered) {
+            return
+        }
+        *self.metered.borrow_mut() = Some(metered);
+        let value = if metered.metered { "yes" } else { "no" };
+        info!("Running metered hook {} {}", hook, value);
+        let child = Command::new(hook)
+            .arg(value)
+            .env("SGSTATUS_METERED", value)
+            .env("SGSTATUS_METERED_GUESSED", if metered.guessed { "yes" } else { "no" })
+            .spawn();
+        match child {
+            Ok(mut child) => {
+                thread::spawn(move || {
+                    match child.wait() {
+                        Ok(status) if status.success() => {},
+                        Ok(status) => error!("Metered hook failed: {}", status),
+                        Err(err) => error!("Could not run metered hook: {:?}", err)
+                    }
+                });
+            },
+            Err(err) => error!("Could not run metered hook: {:?}", err)
+        }
+    }
+
+    // Samples the primary interface, the throughput item is only updated when
+    // the rates change
+    fn sample_throughput(&self, throughput_chan: &Sender<StatusUpdate>) {
+        let throughput = match self.throughput {
+            Some(ref throughput) => throughput,
+            None => return
+        };
+        let interface = self.details.borrow().as_ref()
+            .map(|details| details.interface.clone())
+            .filter(|interface| !interface.is_empty());
+        if throughput.sample(interface.as_ref().map(|interface| interface.as_str())) {
+            self.send_throughput_status(throughput_chan);
+        }
+    }
+
+    // The base connection and its interface while data usage is counted
+    // for it
+    fn get_tracked_connection(&self) -> Option<(ActiveConnection, String)> {
+        let data_usage = match self.data_usage {
+            Some(ref data_usage) => data_usage,
+            None => return None
+        };
+        let metered = self.get_metered().map(|metered| metered.metered).unwrap_or(false);
+        if !data_usage.is_tracked(metered) {
+            return None
+        }
+        let interface = match *self.details.borrow() {
+            Some(ref details) if !details.interface.is_empty() => details.interface.clone(),
+            _ => return None
+        };
+        self.get_base_connection().map(|connection| (connection, interface))
+    }
+
+    fn get_data_usage_description(&self) -> Option<String> {
+        match (&self.data_usage, self.get_tracked_connection()) {
+            (&Some(ref data_usage), Some((connection, _))) => {
+                data_usage.description(&connection.uuid)
+            },
+            _ => None
+        }
+    }
+
+    // Returns whether the usage shown in the tooltip changed
+    fn sample_data_usage(&self) -> bool {
+        let data_usage = match self.data_usage {
+            Some(ref data_usage) => data_usage,
+            None => return false
+        };
+        let tracked = self.get_tracked_connection();
+        let description = self.get_data_usage_description();
+        let connection = tracked.as_ref()
+            .map(|&(ref connection, ref interface)| (connection.uuid.as_str(), interface.as_str()));
+        if !data_usage.sample(connection) {
+            return false
+        }
+        if let Some((ref connection, _)) = tracked {
+            data_usage.check_cap(&connection.uuid, &connection.name);
+        }
+        self.get_data_usage_description() != description
+    }
+
+    // Whether the base connection is activated and its interface is up
+    fn is_interface_up(&self) -> bool {
+        let activated = self.get_base_connection()
+            .map(|connection| connection.is_activated())
+            .unwrap_or(false);
+        activated && match *self.details.borrow() {
+            Some(ref details) if !details.interface.is_empty() => {
+                throughput::is_interface_up(&details.interface)
+            },
+            _ => false
+        }
+    }
+
+    // Throughput sets the pace when it samples more often than data usage
+    fn get_tick_interval(&self) -> Option<Duration> {
+        let throughput = self.throughput.as_ref()
+            .filter(|_| self.is_interface_up())
+            .map(|throughput| throughput.interval);
+        let data_usage = self.get_tracked_connection()
+            .filter(|_| self.is_interface_up())
+            .map(|_| Duration::from_secs(data_usage::DEFAULT_INTERVAL));
+        match (throughput, data_usage) {
+            (Some(throughput), Some(data_usage)) => Some(throughput.min(data_usage)),
+            (throughput, data_usage) => throughput.or(data_usage)
+        }
+    }
+
+    // Starts, restarts or stops the sampling timer when the tick interval
+    // changes, returns whether sampling stopped
+    fn update_ticks(&self, handle: &Handle, ticks: &UnboundedSender<Event>) -> bool {
+        let tick_interval = self.get_tick_interval();
+        let running = self.ticks.borrow().as_ref().map(|&(interval, _)| interval);
+        if running == tick_interval {
+            return false
+        }
+        *self.ticks.borrow_mut() = None;
+        let tick_interval = match tick_interval {
+            Some(tick_interval) => tick_interval,
+            None => {
+                info!("Stopping network sampling");
+                return true
+            }
+        };
+        let interval = match Interval::new(tick_interval, handle) {
+            Ok(interval) => interval,
+            Err(err) => {
+                error!("Could not start network sampling interval: {:?}", err);
+                return running.is_some()
+            }
+        };
+        info!("Sampling network every {:?}", tick_interval);
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let ticks = ticks.clone();
+        let timer = interval
+            .map_err(|err| error!("Network sampling interval failed: {:?}", err))
+            .for_each(move |_| ticks.unbounded_send(Event::Tick).map_err(|_| ()))
+            .select2(stop_rx)
+            .then(|_| Ok(()));
+        handle.spawn(timer);
+        *self.ticks.borrow_mut() = Some((tick_interval, stop_tx));
+        false
+    }
+
+    fn send_throughput_status(&self, throughput_chan: &Sender<StatusUpdate>) {
+        if let Some(ref throughput) = self.throughput {
+            if let Err(err) = throughput_chan.send(throughput.update_status()) {
+                error!("Could not send throughput status: {}", err);
+            }
+        }
+    }
+
+    pub fn run(&self, out_chan: Sender<StatusUpdate>, vpn_chan: Sender<StatusUpdate>,
+        throughput_chan: Sender<StatusUpdate>) {
         if let Err(err) = self.conn.add_match(
             "type=signal,sender=org.freedesktop.NetworkManager,path=/org/freedesktop/NetworkManager,member=StateChanged")
         {
@@ -148,40 +939,287 @@ impl NetworkMonitor {
                 error!("Network monitor could not add dbus match: {:?}", err);
                 return
         }
+        // Saved connections being added, removed or changed
+        if let Err(err) = self.conn.add_match(
+            "type=signal,sender=org.freedesktop.NetworkManager,path=/org/freedesktop/NetworkManager/Settings,interface=org.freedesktop.NetworkManager.Settings")
+        {
+                error!("Network monitor could not add dbus match: {:?}", err);
+                return
+        }
+        if let Err(err) = self.conn.add_match(
+            "type=signal,sender=org.freedesktop.NetworkManager,path_namespace=/org/freedesktop/NetworkManager/Settings,interface=org.freedesktop.NetworkManager.Settings.Connection,member=Updated")
+        {
+                error!("Network monitor could not add dbus match: {:?}", err);
+                return
+        }
         let mut core = Core::new().unwrap();
         let aconn = AConnection::new(self.conn.clone(), core.handle()).unwrap();
-        let messages = aconn.messages().unwrap();
-        let signals = messages.for_each(|m| {
-            info!("Incoming signal: {:?}", m);
-            let headers = m.headers();
-            if let Some(member) = headers.3 {
-                if member == "StateChanged" || member == "PropertiesChanged" {
-                    let icon = self.update_status();
-                    match out_chan.send(icon.clone()) {
-                        Ok(_) => info!("Sent network icon: {}", icon),
-                        Err(err) => error!("Could not send network icon: {} - {}", icon, err)
+        let messages = aconn.messages().unwrap().map(Event::Signal);
+        // The timer only runs while there is something to sample
+        let handle = core.handle();
+        let (ticks_tx, ticks) = mpsc::unbounded();
+        self.update_ticks(&handle, &ticks_tx);
+        let events = messages.select(ticks).for_each(|event| {
+            match event {
+                Event::Signal(m) => {
+                    info!("Incoming signal: {:?}", m);
+                    if self.update_properties(&m) {
+                        self.send_status(&out_chan, &vpn_chan);
+                    }
+                },
+                Event::Tick => {
+                    self.sample_throughput(&throughput_chan);
+                    if self.sample_data_usage() {
+                        self.send_status(&out_chan, &vpn_chan);
                     }
                 }
             }
+            // A last sample hides the throughput item and saves the data
+            // usage of the connection that went down
+            if self.update_ticks(&handle, &ticks_tx) {
+                self.sample_throughput(&throughput_chan);
+                if self.sample_data_usage() {
+                    self.send_status(&out_chan, &vpn_chan);
+                }
+            }
             Ok(())
         });
-        core.run(signals).unwrap();   
+        core.run(events).unwrap();   
     }
 }
 
-pub fn monitor_network<'a>(out_chan: Sender<String>) {
-    match NetworkMonitor::new() {
+// Whether the primary connection is metered, either set on the connection or
+// guessed by NetworkManager, e.g. from a phone's Wi-Fi hotspot
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Metered {
+    metered: bool,
+    guessed: bool
+}
+
+impl Metered {
+    // Tooltip line, None for connections that aren't metered
+    fn description(&self) -> Option<String> {
+        match (self.metered, self.guessed) {
+            (true, false) => Some(String::from("Metered connection")),
+            (true, true) => Some(String::from("Metered connection (guessed)")),
+            (false, _) => None
+        }
+    }
+}
+
+// None while NetworkManager doesn't know
+fn get_metered(metered: i64) -> Option<Metered> {
+    let (metered, guessed) = match metered {
+        NM_METERED_YES => (true, false),
+        NM_METERED_GUESS_YES => (true, true),
+        NM_METERED_NO => (false, false),
+        NM_METERED_GUESS_NO => (false, true),
+        NM_METERED_UNKNOWN | _ => return None
+    };
+    Some(Metered {
+        metered: metered,
+        guessed: guessed
+    })
+}
+
+// A captive portal or limited connectivity shows the no-route icon even when
+// NetworkManager still reports global connectivity, e.g. right after
+// connecting and before its own state catches up
+fn get_connectivity_state(state: i64, connectivity: i64) -> i64 {
+    match connectivity {
+        NM_CONNECTIVITY_PORTAL | NM_CONNECTIVITY_LIMITED if state >= NM_STATE_CONNECTED_LOCAL => {
+            NM_STATE_CONNECTED_SITE
+        },
+        _ => state
+    }
+}
+
+// Tooltip line, None when connectivity is full or unknown
+fn get_connectivity_description(connectivity: i64) -> Option<String> {
+    match connectivity {
+        NM_CONNECTIVITY_NONE => Some(String::from("No network access")),
+        NM_CONNECTIVITY_PORTAL => Some(String::from("Sign-in required to reach the internet")),
+        NM_CONNECTIVITY_LIMITED => Some(String::from("Limited connectivity")),
+        NM_CONNECTIVITY_UNKNOWN | NM_CONNECTIVITY_FULL | _ => None
+    }
+}
+
+fn connect_network_manager<'a>(conn: &'a Connection) -> ConnPath<'a, &'a Connection> {
+    conn.with_path("org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000)
+}
+
+fn open_portal(url: &str) {
+    info!("Opening captive portal page {}", url);
+    match Command::new("xdg-open").arg(url).spawn() {
+        Ok(mut child) => {
+            thread::spawn(move || {
+                if let Err(err) = child.wait() {
+                    error!("Could not open captive portal page: {:?}", err);
+                }
+            });
+        },
+        Err(err) => error!("Could not run xdg-open: {:?}", err)
+    }
+}
+
+// CheckConnectivity only returns once the check is done, which can take a
+// while, so it runs on a thread of its own. The monitor picks up the result
+// from NetworkManager's PropertiesChanged signal.
+fn check_connectivity() {
+    info!("Checking network connectivity");
+    thread::spawn(|| {
+        let conn = match Connection::get_private(BusType::System) {
+            Ok(c) => c,
+            Err(err) => {
+                error!("Could not connect to the system bus: {:?}", err);
+                return
+            }
+        };
+        let c_path = conn.with_path(
+            "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 60000);
+        match c_path.check_connectivity() {
+            Ok(connectivity) => info!("Network connectivity: {}", connectivity),
+            Err(err) => error!("Could not check network connectivity: {:?}", err)
+        }
+    });
+}
+
+// Clicking the network item behind a captive portal opens the portal's
+// sign-in page, unless `[network] open_portal` is off, and the secondary
+// click toggles airplane mode. Otherwise a click brings the saved connection
+// named by `[network] click_connection` up or down. The menu can also
+// recheck connectivity, connect to Wi-Fi networks, switch between saved
+// connections and turn the radios on and off.
+pub fn get_action_handler(config: &Config) -> Result<ActionHandler, Error> {
+    let conn = try!(Connection::get_private(BusType::System));
+    let click_opens_portal = config.get_bool("network", "open_portal").unwrap_or(true);
+    let portal_url = String::from(config.get_str("network", "portal_url")
+        .unwrap_or(DEFAULT_PORTAL_URL));
+    let click_connection = config.get_str("network", "click_connection").map(String::from);
+    Ok(Box::new(move |action| {
+        match action {
+            Action::Activate => {
+                let connectivity = match connect_network_manager(&conn).get_connectivity() {
+                    Ok(connectivity) => connectivity as i64,
+                    Err(err) => {
+                        error!("Could not get network connectivity: {:?}", err);
+                        NM_CONNECTIVITY_UNKNOWN
+                    }
+                };
+                if click_opens_portal && connectivity == NM_CONNECTIVITY_PORTAL {
+                    open_portal(&portal_url)
+                } else if let Some(ref name) = click_connection {
+                    saved_connection::toggle(name)
+                }
+            },
+            Action::MenuItem(ref item) if item == ACTION_OPEN_PORTAL => open_portal(&portal_url),
+            Action::MenuItem(ref item) if item == ACTION_CHECK_CONNECTIVITY => check_connectivity(),
+            Action::MenuItem(ref item) => {
+                if !radio::run_action(&conn, item) && !wifi::run_action(&conn, item) &&
+                    !saved_connection::run_action(item) {
+                    error!("Unknown network action: {}", item);
+                }
+            },
+            Action::SecondaryActivate => radio::toggle_airplane_mode(&conn),
+            _ => {}
+        }
+    }))
+}
+
+fn get_properties_match(path: &str) -> String {
+    format!("type=signal,sender=org.freedesktop.NetworkManager,path={},interface=org.freedesktop.DBus.Properties,member=PropertiesChanged", path)
+}
+
+pub fn monitor_network<'a>(out_chan: Sender<StatusUpdate>, vpn_chan: Sender<StatusUpdate>,
+    throughput_chan: Sender<StatusUpdate>, config: Config) {
+    match NetworkMonitor::new(&config) {
         Ok(monitor) => {
             info!("Starting network monitor");
-            let icon = monitor.update_status();
-            match out_chan.send(icon.clone()) {
-                Ok(_) => info!("Sent initial network icon: {}", icon.clone()),
-                Err(err) => error!("Could not send network initial icon: {} - {}", icon, err)
-            }
-            monitor.run(out_chan);
+            monitor.load_properties();
+            monitor.send_status(&out_chan, &vpn_chan);
+            monitor.send_throughput_status(&throughput_chan);
+            monitor.run(out_chan, vpn_chan, throughput_chan);
         },
         Err(err) => {
             error!("Could not start network monitor: {:?}", err);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_types_map_to_kinds() {
+        let cases = [
+            ("802-11-wireless", ConnectionKind::Wireless),
+            ("802-3-ethernet", ConnectionKind::Wired),
+            ("gsm", ConnectionKind::Cellular),
+            ("cdma", ConnectionKind::Cellular),
+            ("bluetooth", ConnectionKind::Bluetooth),
+            ("bridge", ConnectionKind::Wired),
+            ("bond", ConnectionKind::Wired),
+            ("vlan", ConnectionKind::Wired),
+            ("wireguard", ConnectionKind::Vpn),
+            ("vpn", ConnectionKind::Vpn),
+            ("loopback", ConnectionKind::Loopback),
+            ("some-future-type", ConnectionKind::Wired)
+        ];
+        for &(connection_type, kind) in cases.iter() {
+            assert_eq!(get_connection_kind(connection_type), kind, "{}", connection_type);
+        }
+    }
+
+    #[test]
+    fn connection_types_are_unique() {
+        for (index, &(name, _)) in CONNECTION_TYPES.iter().enumerate() {
+            assert!(CONNECTION_TYPES[index + 1..].iter().all(|&(other, _)| other != name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn icons_follow_state() {
+        let cases = [
+            (ConnectionKind::Wired, NM_STATE_CONNECTED_GLOBAL, "network-transmit-receive-symbolic"),
+            (ConnectionKind::Wired, NM_STATE_CONNECTED_SITE, "network-wired-no-route-symbolic"),
+            (ConnectionKind::Wired, NM_STATE_CONNECTING, "network-wired-acquiring-symbolic"),
+            (ConnectionKind::Wired, NM_STATE_DISCONNECTED, "network-wired-disconnected-symbolic"),
+            (ConnectionKind::Wired, NM_STATE_STATE_ASLEEP, "network-wired-offline-symbolic"),
+            (ConnectionKind::Wireless, NM_STATE_CONNECTED_SITE, "network-wireless-no-route-symbolic"),
+            (ConnectionKind::Wireless, NM_STATE_CONNECTING, "network-wireless-acquiring-symbolic"),
+            (ConnectionKind::Wireless, NM_STATE_DISCONNECTED, "network-wireless-disconnected-symbolic"),
+            (ConnectionKind::Cellular, NM_STATE_CONNECTED_GLOBAL, "network-cellular-connected-symbolic"),
+            (ConnectionKind::Cellular, NM_STATE_CONNECTED_SITE, "network-cellular-no-route-symbolic"),
+            (ConnectionKind::Cellular, NM_STATE_CONNECTING, "network-cellular-acquiring-symbolic"),
+            (ConnectionKind::Cellular, NM_STATE_DISCONNECTED, "network-cellular-offline-symbolic"),
+            (ConnectionKind::Bluetooth, NM_STATE_CONNECTED_GLOBAL, "bluetooth-active-symbolic"),
+            (ConnectionKind::Bluetooth, NM_STATE_CONNECTING, "network-wired-acquiring-symbolic"),
+            (ConnectionKind::Vpn, NM_STATE_CONNECTED_GLOBAL, "network-vpn-symbolic"),
+            (ConnectionKind::Vpn, NM_STATE_CONNECTING, "network-vpn-acquiring-symbolic"),
+            (ConnectionKind::Vpn, NM_STATE_CONNECTED_SITE, "network-wired-no-route-symbolic"),
+            (ConnectionKind::Loopback, NM_STATE_CONNECTED_GLOBAL, "network-wired-disconnected-symbolic")
+        ];
+        for &(kind, state, icon) in cases.iter() {
+            assert_eq!(get_icon(kind, state, None), icon, "{:?} {}", kind, state);
+        }
+    }
+
+    #[test]
+    fn wireless_icon_follows_strength() {
+        let cases = [
+            (None, "network-wireless-signal-excellent-symbolic"),
+            (Some(0), "network-wireless-signal-none-symbolic"),
+            (Some(19), "network-wireless-signal-none-symbolic"),
+            (Some(20), "network-wireless-signal-weak-symbolic"),
+            (Some(45), "network-wireless-signal-ok-symbolic"),
+            (Some(79), "network-wireless-signal-good-symbolic"),
+            (Some(80), "network-wireless-signal-excellent-symbolic"),
+            (Some(100), "network-wireless-signal-excellent-symbolic")
+        ];
+        for &(strength, icon) in cases.iter() {
+            assert_eq!(get_icon(ConnectionKind::Wireless, NM_STATE_CONNECTED_GLOBAL, strength),
+                icon, "{:?}", strength);
+        }
+    }
+}
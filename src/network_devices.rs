@@ -0,0 +1,273 @@
+use dbus::Connection;
+use dbus::BusType;
+use dbus::Error;
+use dbus::SignalArgs;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::thread;
+use tokio_core::reactor::Core;
+use futures::{Stream};
+use dbus_tokio::AConnection;
+use network;
+use network::ConnectionKind;
+use network_manager::OrgFreedesktopNetworkManager;
+use network_manager::OrgFreedesktopNetworkManagerDevice;
+use network_manager::OrgFreedesktopNetworkManagerDeviceAdded;
+use network_manager::OrgFreedesktopNetworkManagerDeviceRemoved;
+use network_manager::OrgFreedesktopNetworkManagerDeviceStateChanged;
+use output::Block;
+use status;
+use status::StatusUpdate;
+use std::sync::mpsc::{channel, Sender};
+
+// NMDeviceType
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMDeviceType
+const NM_DEVICE_TYPE_WIFI: u32 = 2;
+const NM_DEVICE_TYPE_BT: u32 = 5;
+const NM_DEVICE_TYPE_OLPC_MESH: u32 = 6;
+const NM_DEVICE_TYPE_WIMAX: u32 = 7;
+const NM_DEVICE_TYPE_MODEM: u32 = 8;
+const NM_DEVICE_TYPE_TUN: u32 = 16;
+const NM_DEVICE_TYPE_IP_TUNNEL: u32 = 17;
+const NM_DEVICE_TYPE_WIREGUARD: u32 = 29;
+const NM_DEVICE_TYPE_WIFI_P2P: u32 = 30;
+const NM_DEVICE_TYPE_LOOPBACK: u32 = 32;
+
+// NMDeviceState
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NMDeviceState
+const NM_DEVICE_STATE_UNMANAGED: u32 = 10;
+const NM_DEVICE_STATE_UNAVAILABLE: u32 = 20;
+const NM_DEVICE_STATE_DISCONNECTED: u32 = 30;
+const NM_DEVICE_STATE_PREPARE: u32 = 40;
+const NM_DEVICE_STATE_NEED_AUTH: u32 = 60;
+const NM_DEVICE_STATE_SECONDARIES: u32 = 90;
+const NM_DEVICE_STATE_ACTIVATED: u32 = 100;
+const NM_DEVICE_STATE_DEACTIVATING: u32 = 110;
+const NM_DEVICE_STATE_FAILED: u32 = 120;
+
+struct Device {
+    interface: String,
+    device_type: u32,
+    state: u32
+}
+
+fn get_device_kind(device_type: u32) -> ConnectionKind {
+    match device_type {
+        NM_DEVICE_TYPE_WIFI | NM_DEVICE_TYPE_OLPC_MESH | NM_DEVICE_TYPE_WIMAX |
+        NM_DEVICE_TYPE_WIFI_P2P => ConnectionKind::Wireless,
+        NM_DEVICE_TYPE_MODEM => ConnectionKind::Cellular,
+        NM_DEVICE_TYPE_BT => ConnectionKind::Bluetooth,
+        NM_DEVICE_TYPE_TUN | NM_DEVICE_TYPE_IP_TUNNEL | NM_DEVICE_TYPE_WIREGUARD => {
+            ConnectionKind::Vpn
+        },
+        NM_DEVICE_TYPE_LOOPBACK => ConnectionKind::Loopback,
+        _ => ConnectionKind::Wired
+    }
+}
+
+// The NMState a device's state corresponds to, so that devices share the
+// network item's icons
+fn get_network_state(state: u32) -> i64 {
+    match state {
+        NM_DEVICE_STATE_ACTIVATED => network::NM_STATE_CONNECTED_GLOBAL,
+        NM_DEVICE_STATE_PREPARE ... NM_DEVICE_STATE_SECONDARIES => network::NM_STATE_CONNECTING,
+        NM_DEVICE_STATE_DEACTIVATING => network::NM_STATE_DISCONNECTING,
+        NM_DEVICE_STATE_UNAVAILABLE => network::NM_STATE_STATE_ASLEEP,
+        _ => network::NM_STATE_DISCONNECTED
+    }
+}
+
+fn get_state_description(state: u32) -> String {
+    match state {
+        NM_DEVICE_STATE_UNAVAILABLE => String::from("unavailable"),
+        NM_DEVICE_STATE_DISCONNECTED => String::from("disconnected"),
+        NM_DEVICE_STATE_NEED_AUTH => String::from("needs authentication"),
+        NM_DEVICE_STATE_PREPARE ... NM_DEVICE_STATE_SECONDARIES => String::from("connecting"),
+        NM_DEVICE_STATE_ACTIVATED => String::from("connected"),
+        NM_DEVICE_STATE_DEACTIVATING => String::from("disconnecting"),
+        NM_DEVICE_STATE_FAILED => String::from("failed"),
+        _ => String::from("unknown")
+    }
+}
+
+// Shows one item per NetworkManager device, enabled by
+// `[network] per_device`. Items come and go as devices are plugged in and
+// removed, like peripherals.
+pub struct DeviceMonitor {
+    conn: Rc<Connection>,
+    text_chan: Sender<Block>
+}
+
+impl DeviceMonitor {
+    pub fn new(text_chan: Sender<Block>) -> Result<DeviceMonitor, Error> {
+        match Connection::get_private(BusType::System) {
+            Ok(c) => {
+                Ok(DeviceMonitor {
+                    conn: Rc::new(c),
+                    text_chan: text_chan
+                })
+            },
+            Err(err) => {
+                Err(err)
+            }
+        }
+    }
+
+    fn get_devices(&self) -> Vec<String> {
+        let c_path = self.conn.with_path(
+            "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+        match OrgFreedesktopNetworkManager::get_devices(&c_path) {
+            Ok(devices) => devices.iter().map(|p| p.to_string()).collect(),
+            Err(err) => {
+                error!("Could not get network devices: {:?}", err);
+                Vec::new()
+            }
+        }
+    }
+
+    // None for devices that NetworkManager doesn't manage and for loopback
+    fn get_device(&self, path: &str) -> Option<Device> {
+        let c_path = self.conn.with_path("org.freedesktop.NetworkManager", path, 5000);
+        let device_type = match c_path.get_device_type() {
+            Ok(NM_DEVICE_TYPE_LOOPBACK) => return None,
+            Ok(device_type) => device_type,
+            Err(err) => {
+                error!("Could not get type of {}: {:?}", path, err);
+                return None
+            }
+        };
+        let state = match OrgFreedesktopNetworkManagerDevice::get_state(&c_path) {
+            Ok(NM_DEVICE_STATE_UNMANAGED) => return None,
+            Ok(state) => state,
+            Err(err) => {
+                error!("Could not get state of {}: {:?}", path, err);
+                return None
+            }
+        };
+        let interface = match c_path.get_interface() {
+            Ok(interface) => interface,
+            Err(err) => {
+                error!("Could not get interface of {}: {:?}", path, err);
+                return None
+            }
+        };
+        Some(Device {
+            interface: interface,
+            device_type: device_type,
+            state: state
+        })
+    }
+
+    fn update_status(&self, device: &Device) -> StatusUpdate {
+        let kind = get_device_kind(device.device_type);
+        let mut update = StatusUpdate::new(
+            network::get_icon(kind, get_network_state(device.state), None));
+        update.text = format!("{} {}", device.interface, get_state_description(device.state));
+        update.tooltip = format!("{}: {}", device.interface, get_state_description(device.state));
+        update
+    }
+
+    fn show_item(&self, device: &Device) -> Sender<StatusUpdate> {
+        let (item_tx, item_rx) = channel();
+        let title = device.interface.clone();
+        let name = format!("network-{}", device.interface);
+        let text_chan = self.text_chan.clone();
+        let notifier_text_chan = self.text_chan.clone();
+        thread::spawn(move || {
+            if let Ok(mut notifier) = status::StatusNotifier::new(&name, text_chan) {
+                info!("Starting network device notifier for {}", name);
+                notifier.set_title(title);
+                if let Err(err) = notifier.run(item_rx) {
+                    error!("Could not start network device notifier: {:?}", err)
+                }
+                info!("Stopped network device notifier for {}", name);
+                // Clear the text the item left on the status line
+                let block = Block {
+                    id: name.clone(),
+                    text: String::new(),
+                    color: String::new()
+                };
+                if let Err(err) = notifier_text_chan.send(block) {
+                    error!("Could not clear network device text: {:?}", err);
+                }
+            }
+        });
+        item_tx
+    }
+
+    // Shows, updates or hides the item for the device at `path`
+    fn update_item(&self, items: &mut HashMap<String, Sender<StatusUpdate>>, path: &str) {
+        match self.get_device(path) {
+            Some(device) => {
+                if !items.contains_key(path) {
+                    let item_tx = self.show_item(&device);
+                    items.insert(path.to_string(), item_tx);
+                }
+                let update = self.update_status(&device);
+                let icon = update.icon_name.clone();
+                if let Some(item_tx) = items.get(path) {
+                    match item_tx.send(update) {
+                        Ok(_) => info!("Sent network device icon: {} - {}", path, icon),
+                        Err(err) => error!("Could not send network device icon: {} - {}", icon, err)
+                    }
+                }
+            },
+            None => {
+                // Dropping the sender stops the notifier thread, which
+                // unregisters the item from the watcher
+                if items.remove(path).is_some() {
+                    info!("Hiding network device: {}", path);
+                }
+            }
+        }
+    }
+
+    pub fn run(&self) {
+        let rules = [
+            "type=signal,sender=org.freedesktop.NetworkManager,path=/org/freedesktop/NetworkManager,member=DeviceAdded",
+            "type=signal,sender=org.freedesktop.NetworkManager,path=/org/freedesktop/NetworkManager,member=DeviceRemoved",
+            "type=signal,sender=org.freedesktop.NetworkManager,path_namespace=/org/freedesktop/NetworkManager/Devices,interface=org.freedesktop.NetworkManager.Device,member=StateChanged"
+        ];
+        for rule in rules.iter() {
+            if let Err(err) = self.conn.add_match(rule) {
+                error!("Network device monitor could not add dbus match: {:?}", err);
+                return
+            }
+        }
+        let mut items: HashMap<String, Sender<StatusUpdate>> = HashMap::new();
+        for path in self.get_devices() {
+            self.update_item(&mut items, &path);
+        }
+        let mut core = Core::new().unwrap();
+        let aconn = AConnection::new(self.conn.clone(), core.handle()).unwrap();
+        let messages = aconn.messages().unwrap();
+        let signals = messages.for_each(|m| {
+            if let Some(added) = OrgFreedesktopNetworkManagerDeviceAdded::from_message(&m) {
+                self.update_item(&mut items, &added.device_path);
+            } else if let Some(removed) = OrgFreedesktopNetworkManagerDeviceRemoved::from_message(&m) {
+                if items.remove(&removed.device_path.to_string()).is_some() {
+                    info!("Network device removed: {}", removed.device_path);
+                }
+            } else if OrgFreedesktopNetworkManagerDeviceStateChanged::from_message(&m).is_some() {
+                if let Some(path) = m.path() {
+                    self.update_item(&mut items, &path);
+                }
+            }
+            Ok(())
+        });
+        core.run(signals).unwrap();
+    }
+}
+
+pub fn monitor_network_devices(text_chan: Sender<Block>) {
+    match DeviceMonitor::new(text_chan) {
+        Ok(monitor) => {
+            info!("Starting network device monitor");
+            monitor.run();
+        },
+        Err(err) => {
+            error!("Could not start network device monitor: {:?}", err);
+        }
+    }
+}
@@ -532,3 +532,697 @@ impl dbus::SignalArgs for OrgFreedesktopNetworkManagerDeviceRemoved {
         Ok(())
     }
 }
+
+pub trait OrgFreedesktopNetworkManagerConnectionActive {
+    type Err;
+    fn get_connection(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_specific_object(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_id(&self) -> Result<String, Self::Err>;
+    fn get_uuid(&self) -> Result<String, Self::Err>;
+    fn get_type(&self) -> Result<String, Self::Err>;
+    fn get_devices(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_state(&self) -> Result<u32, Self::Err>;
+    fn get_state_flags(&self) -> Result<u32, Self::Err>;
+    fn get_default(&self) -> Result<bool, Self::Err>;
+    fn get_ip4_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_dhcp4_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_default6(&self) -> Result<bool, Self::Err>;
+    fn get_ip6_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_dhcp6_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_vpn(&self) -> Result<bool, Self::Err>;
+    fn get_master(&self) -> Result<dbus::Path<'static>, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerConnectionActive for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_connection(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Connection")
+    }
+
+    fn get_specific_object(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "SpecificObject")
+    }
+
+    fn get_id(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Id")
+    }
+
+    fn get_uuid(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Uuid")
+    }
+
+    fn get_type(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Type")
+    }
+
+    fn get_devices(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Devices")
+    }
+
+    fn get_state(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "State")
+    }
+
+    fn get_state_flags(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "StateFlags")
+    }
+
+    fn get_default(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Default")
+    }
+
+    fn get_ip4_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Ip4Config")
+    }
+
+    fn get_dhcp4_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Dhcp4Config")
+    }
+
+    fn get_default6(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Default6")
+    }
+
+    fn get_ip6_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Ip6Config")
+    }
+
+    fn get_dhcp6_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Dhcp6Config")
+    }
+
+    fn get_vpn(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Vpn")
+    }
+
+    fn get_master(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Connection.Active", "Master")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerConnectionActiveStateChanged {
+    pub state: u32,
+    pub reason: u32,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerConnectionActiveStateChanged {
+    const NAME: &'static str = "StateChanged";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Connection.Active";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.state as &arg::RefArg).append(i);
+        (&self.reason as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.state = try!(i.read());
+        self.reason = try!(i.read());
+        Ok(())
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerDevice {
+    type Err;
+    fn disconnect(&self) -> Result<(), Self::Err>;
+    fn delete(&self) -> Result<(), Self::Err>;
+    fn get_udi(&self) -> Result<String, Self::Err>;
+    fn get_interface(&self) -> Result<String, Self::Err>;
+    fn get_ip_interface(&self) -> Result<String, Self::Err>;
+    fn get_driver(&self) -> Result<String, Self::Err>;
+    fn get_driver_version(&self) -> Result<String, Self::Err>;
+    fn get_firmware_version(&self) -> Result<String, Self::Err>;
+    fn get_capabilities(&self) -> Result<u32, Self::Err>;
+    fn get_state(&self) -> Result<u32, Self::Err>;
+    fn get_active_connection(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_ip4_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_dhcp4_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_ip6_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_dhcp6_config(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_managed(&self) -> Result<bool, Self::Err>;
+    fn get_autoconnect(&self) -> Result<bool, Self::Err>;
+    fn get_firmware_missing(&self) -> Result<bool, Self::Err>;
+    fn get_nm_plugin_missing(&self) -> Result<bool, Self::Err>;
+    fn get_device_type(&self) -> Result<u32, Self::Err>;
+    fn get_available_connections(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_physical_port_id(&self) -> Result<String, Self::Err>;
+    fn get_mtu(&self) -> Result<u32, Self::Err>;
+    fn get_metered(&self) -> Result<u32, Self::Err>;
+    fn get_real(&self) -> Result<bool, Self::Err>;
+    fn set_managed(&self, value: bool) -> Result<(), Self::Err>;
+    fn set_autoconnect(&self, value: bool) -> Result<(), Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerDevice for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn disconnect(&self) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Device".into(), &"Disconnect".into(), |_| {
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Device".into(), &"Delete".into(), |_| {
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn get_udi(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Udi")
+    }
+
+    fn get_interface(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Interface")
+    }
+
+    fn get_ip_interface(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "IpInterface")
+    }
+
+    fn get_driver(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Driver")
+    }
+
+    fn get_driver_version(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "DriverVersion")
+    }
+
+    fn get_firmware_version(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "FirmwareVersion")
+    }
+
+    fn get_capabilities(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Capabilities")
+    }
+
+    fn get_state(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "State")
+    }
+
+    fn get_active_connection(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "ActiveConnection")
+    }
+
+    fn get_ip4_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Ip4Config")
+    }
+
+    fn get_dhcp4_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Dhcp4Config")
+    }
+
+    fn get_ip6_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Ip6Config")
+    }
+
+    fn get_dhcp6_config(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Dhcp6Config")
+    }
+
+    fn get_managed(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Managed")
+    }
+
+    fn get_autoconnect(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Autoconnect")
+    }
+
+    fn get_firmware_missing(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "FirmwareMissing")
+    }
+
+    fn get_nm_plugin_missing(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "NmPluginMissing")
+    }
+
+    fn get_device_type(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "DeviceType")
+    }
+
+    fn get_available_connections(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "AvailableConnections")
+    }
+
+    fn get_physical_port_id(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "PhysicalPortId")
+    }
+
+    fn get_mtu(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Mtu")
+    }
+
+    fn get_metered(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Metered")
+    }
+
+    fn get_real(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device", "Real")
+    }
+
+    fn set_managed(&self, value: bool) -> Result<(), Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::set(&self, "org.freedesktop.NetworkManager.Device", "Managed", value)
+    }
+
+    fn set_autoconnect(&self, value: bool) -> Result<(), Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::set(&self, "org.freedesktop.NetworkManager.Device", "Autoconnect", value)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerDeviceStateChanged {
+    pub new_state: u32,
+    pub old_state: u32,
+    pub reason: u32,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerDeviceStateChanged {
+    const NAME: &'static str = "StateChanged";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Device";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.new_state as &arg::RefArg).append(i);
+        (&self.old_state as &arg::RefArg).append(i);
+        (&self.reason as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.new_state = try!(i.read());
+        self.old_state = try!(i.read());
+        self.reason = try!(i.read());
+        Ok(())
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerDeviceWireless {
+    type Err;
+    fn get_access_points(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_all_access_points(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn request_scan(&self, options: ::std::collections::HashMap<&str, arg::Variant<Box<arg::RefArg>>>) -> Result<(), Self::Err>;
+    fn get_hw_address(&self) -> Result<String, Self::Err>;
+    fn get_perm_hw_address(&self) -> Result<String, Self::Err>;
+    fn get_mode(&self) -> Result<u32, Self::Err>;
+    fn get_bitrate(&self) -> Result<u32, Self::Err>;
+    //fn get_access_points(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_active_access_point(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_wireless_capabilities(&self) -> Result<u32, Self::Err>;
+    fn get_last_scan(&self) -> Result<i64, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerDeviceWireless for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_access_points(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Device.Wireless".into(), &"GetAccessPoints".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let access_points: Vec<dbus::Path<'static>> = try!(i.read());
+        Ok(access_points)
+    }
+
+    fn get_all_access_points(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Device.Wireless".into(), &"GetAllAccessPoints".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let access_points: Vec<dbus::Path<'static>> = try!(i.read());
+        Ok(access_points)
+    }
+
+    fn request_scan(&self, options: ::std::collections::HashMap<&str, arg::Variant<Box<arg::RefArg>>>) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Device.Wireless".into(), &"RequestScan".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(options);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn get_hw_address(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "HwAddress")
+    }
+
+    fn get_perm_hw_address(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "PermHwAddress")
+    }
+
+    fn get_mode(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "Mode")
+    }
+
+    fn get_bitrate(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "Bitrate")
+    }
+
+    fn get_active_access_point(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "ActiveAccessPoint")
+    }
+
+    fn get_wireless_capabilities(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "WirelessCapabilities")
+    }
+
+    fn get_last_scan(&self) -> Result<i64, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Device.Wireless", "LastScan")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerDeviceWirelessAccessPointAdded {
+    pub access_point: dbus::Path<'static>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerDeviceWirelessAccessPointAdded {
+    const NAME: &'static str = "AccessPointAdded";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Device.Wireless";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.access_point as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.access_point = try!(i.read());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerDeviceWirelessAccessPointRemoved {
+    pub access_point: dbus::Path<'static>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerDeviceWirelessAccessPointRemoved {
+    const NAME: &'static str = "AccessPointRemoved";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Device.Wireless";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.access_point as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.access_point = try!(i.read());
+        Ok(())
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerAccessPoint {
+    type Err;
+    fn get_flags(&self) -> Result<u32, Self::Err>;
+    fn get_wpa_flags(&self) -> Result<u32, Self::Err>;
+    fn get_rsn_flags(&self) -> Result<u32, Self::Err>;
+    fn get_ssid(&self) -> Result<Vec<u8>, Self::Err>;
+    fn get_frequency(&self) -> Result<u32, Self::Err>;
+    fn get_hw_address(&self) -> Result<String, Self::Err>;
+    fn get_mode(&self) -> Result<u32, Self::Err>;
+    fn get_max_bitrate(&self) -> Result<u32, Self::Err>;
+    fn get_strength(&self) -> Result<u8, Self::Err>;
+    fn get_last_seen(&self) -> Result<i32, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerAccessPoint for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_flags(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "Flags")
+    }
+
+    fn get_wpa_flags(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "WpaFlags")
+    }
+
+    fn get_rsn_flags(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "RsnFlags")
+    }
+
+    fn get_ssid(&self) -> Result<Vec<u8>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "Ssid")
+    }
+
+    fn get_frequency(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "Frequency")
+    }
+
+    fn get_hw_address(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "HwAddress")
+    }
+
+    fn get_mode(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "Mode")
+    }
+
+    fn get_max_bitrate(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "MaxBitrate")
+    }
+
+    fn get_strength(&self) -> Result<u8, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "Strength")
+    }
+
+    fn get_last_seen(&self) -> Result<i32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.AccessPoint", "LastSeen")
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerIP4Config {
+    type Err;
+    fn get_address_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn get_gateway(&self) -> Result<String, Self::Err>;
+    fn get_route_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn get_nameserver_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn get_domains(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_searches(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_dns_options(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_dns_priority(&self) -> Result<i32, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerIP4Config for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_address_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "AddressData")
+    }
+
+    fn get_gateway(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "Gateway")
+    }
+
+    fn get_route_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "RouteData")
+    }
+
+    fn get_nameserver_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "NameserverData")
+    }
+
+    fn get_domains(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "Domains")
+    }
+
+    fn get_searches(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "Searches")
+    }
+
+    fn get_dns_options(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "DnsOptions")
+    }
+
+    fn get_dns_priority(&self) -> Result<i32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP4Config", "DnsPriority")
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerIP6Config {
+    type Err;
+    fn get_address_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn get_gateway(&self) -> Result<String, Self::Err>;
+    fn get_route_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn get_nameservers(&self) -> Result<Vec<Vec<u8>>, Self::Err>;
+    fn get_domains(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_searches(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_dns_options(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_dns_priority(&self) -> Result<i32, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerIP6Config for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_address_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "AddressData")
+    }
+
+    fn get_gateway(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "Gateway")
+    }
+
+    fn get_route_data(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "RouteData")
+    }
+
+    fn get_nameservers(&self) -> Result<Vec<Vec<u8>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "Nameservers")
+    }
+
+    fn get_domains(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "Domains")
+    }
+
+    fn get_searches(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "Searches")
+    }
+
+    fn get_dns_options(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "DnsOptions")
+    }
+
+    fn get_dns_priority(&self) -> Result<i32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.IP6Config", "DnsPriority")
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerSettings {
+    type Err;
+    fn list_connections(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_connection_by_uuid(&self, uuid: &str) -> Result<dbus::Path<'static>, Self::Err>;
+    fn reload_connections(&self) -> Result<bool, Self::Err>;
+    fn get_connections(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_hostname(&self) -> Result<String, Self::Err>;
+    fn get_can_modify(&self) -> Result<bool, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerSettings for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn list_connections(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Settings".into(), &"ListConnections".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let connections: Vec<dbus::Path<'static>> = try!(i.read());
+        Ok(connections)
+    }
+
+    fn get_connection_by_uuid(&self, uuid: &str) -> Result<dbus::Path<'static>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Settings".into(), &"GetConnectionByUuid".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(uuid);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let connection: dbus::Path<'static> = try!(i.read());
+        Ok(connection)
+    }
+
+    fn reload_connections(&self) -> Result<bool, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Settings".into(), &"ReloadConnections".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let status: bool = try!(i.read());
+        Ok(status)
+    }
+
+    fn get_connections(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Settings", "Connections")
+    }
+
+    fn get_hostname(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Settings", "Hostname")
+    }
+
+    fn get_can_modify(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Settings", "CanModify")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerSettingsNewConnection {
+    pub connection: dbus::Path<'static>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerSettingsNewConnection {
+    const NAME: &'static str = "NewConnection";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Settings";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.connection as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.connection = try!(i.read());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerSettingsConnectionRemoved {
+    pub connection: dbus::Path<'static>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerSettingsConnectionRemoved {
+    const NAME: &'static str = "ConnectionRemoved";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Settings";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.connection as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.connection = try!(i.read());
+        Ok(())
+    }
+}
+
+pub trait OrgFreedesktopNetworkManagerSettingsConnection {
+    type Err;
+    fn get_settings(&self) -> Result<::std::collections::HashMap<String, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn delete(&self) -> Result<(), Self::Err>;
+    fn save(&self) -> Result<(), Self::Err>;
+    fn get_unsaved(&self) -> Result<bool, Self::Err>;
+    fn get_flags(&self) -> Result<u32, Self::Err>;
+    fn get_filename(&self) -> Result<String, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNetworkManagerSettingsConnection for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_settings(&self) -> Result<::std::collections::HashMap<String, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Settings.Connection".into(), &"GetSettings".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let settings: ::std::collections::HashMap<String, ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>> = try!(i.read());
+        Ok(settings)
+    }
+
+    fn delete(&self) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Settings.Connection".into(), &"Delete".into(), |_| {
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.NetworkManager.Settings.Connection".into(), &"Save".into(), |_| {
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn get_unsaved(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Settings.Connection", "Unsaved")
+    }
+
+    fn get_flags(&self) -> Result<u32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Settings.Connection", "Flags")
+    }
+
+    fn get_filename(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.NetworkManager.Settings.Connection", "Filename")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNetworkManagerSettingsConnectionUpdated {
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNetworkManagerSettingsConnectionUpdated {
+    const NAME: &'static str = "Updated";
+    const INTERFACE: &'static str = "org.freedesktop.NetworkManager.Settings.Connection";
+    fn append(&self, _: &mut arg::IterAppend) {
+    }
+    fn get(&mut self, _: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+// This code was autogenerated with dbus-codegen-rust, see https://github.com/diwic/dbus-rs
+
+#![allow(dead_code)]
+use dbus as dbus;
+use dbus::arg;
+
+pub trait OrgFreedesktopNotifications {
+    type Err;
+    fn get_capabilities(&self) -> Result<Vec<String>, Self::Err>;
+    fn notify(&self, app_name: &str, replaces_id: u32, app_icon: &str, summary: &str, body: &str, actions: Vec<&str>, hints: ::std::collections::HashMap<&str, arg::Variant<Box<arg::RefArg>>>, expire_timeout: i32) -> Result<u32, Self::Err>;
+    fn close_notification(&self, id: u32) -> Result<(), Self::Err>;
+    fn get_server_information(&self) -> Result<(String, String, String, String), Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopNotifications for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get_capabilities(&self) -> Result<Vec<String>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.Notifications".into(), &"GetCapabilities".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let capabilities: Vec<String> = try!(i.read());
+        Ok(capabilities)
+    }
+
+    fn notify(&self, app_name: &str, replaces_id: u32, app_icon: &str, summary: &str, body: &str, actions: Vec<&str>, hints: ::std::collections::HashMap<&str, arg::Variant<Box<arg::RefArg>>>, expire_timeout: i32) -> Result<u32, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.Notifications".into(), &"Notify".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(app_name);
+            i.append(replaces_id);
+            i.append(app_icon);
+            i.append(summary);
+            i.append(body);
+            i.append(actions);
+            i.append(hints);
+            i.append(expire_timeout);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let id: u32 = try!(i.read());
+        Ok(id)
+    }
+
+    fn close_notification(&self, id: u32) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.Notifications".into(), &"CloseNotification".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(id);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn get_server_information(&self) -> Result<(String, String, String, String), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.Notifications".into(), &"GetServerInformation".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let name: String = try!(i.read());
+        let vendor: String = try!(i.read());
+        let version: String = try!(i.read());
+        let spec_version: String = try!(i.read());
+        Ok((name, vendor, version, spec_version))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNotificationsNotificationClosed {
+    pub id: u32,
+    pub reason: u32,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNotificationsNotificationClosed {
+    const NAME: &'static str = "NotificationClosed";
+    const INTERFACE: &'static str = "org.freedesktop.Notifications";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.id as &arg::RefArg).append(i);
+        (&self.reason as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.id = try!(i.read());
+        self.reason = try!(i.read());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopNotificationsActionInvoked {
+    pub id: u32,
+    pub action_key: String,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopNotificationsActionInvoked {
+    const NAME: &'static str = "ActionInvoked";
+    const INTERFACE: &'static str = "org.freedesktop.Notifications";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.id as &arg::RefArg).append(i);
+        (&self.action_key as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.id = try!(i.read());
+        self.action_key = try!(i.read());
+        Ok(())
+    }
+}
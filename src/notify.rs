@@ -0,0 +1,46 @@
+use dbus::Connection;
+use dbus::BusType;
+use dbus::arg::{RefArg, Variant};
+
+use std::collections::HashMap;
+use notifications::OrgFreedesktopNotifications;
+
+// Urgency hint values
+// https://developer.gnome.org/notification-spec/#urgency-levels
+pub const URGENCY_LOW: u8 = 0;
+pub const URGENCY_NORMAL: u8 = 1;
+pub const URGENCY_CRITICAL: u8 = 2;
+
+// Sends a notification from sgstatus over `conn`, replacing the one with id
+// `replaces_id` (0 for a new one). Critical notifications stay until they
+// are dismissed. Returns the notification's id.
+pub fn send_notification(conn: &Connection, summary: &str, body: &str, icon: &str,
+    urgency: u8, replaces_id: u32, actions: Vec<&str>) -> Option<u32> {
+    let mut hints: HashMap<&str, Variant<Box<RefArg>>> = HashMap::new();
+    hints.insert("urgency", Variant(Box::new(urgency) as Box<RefArg>));
+    let timeout = if urgency == URGENCY_CRITICAL { 0 } else { -1 };
+    let c_path = conn.with_path(
+        "org.freedesktop.Notifications", "/org/freedesktop/Notifications", 5000);
+    match c_path.notify("sgstatus", replaces_id, icon, summary, body, actions, hints, timeout) {
+        Ok(id) => {
+            info!("Sent notification: {}", summary);
+            Some(id)
+        },
+        Err(err) => {
+            error!("Could not send notification {}: {:?}", summary, err);
+            None
+        }
+    }
+}
+
+// Same as `send_notification` over a private session bus connection
+pub fn notify(summary: &str, body: &str, icon: &str, urgency: u8,
+    replaces_id: u32) -> Option<u32> {
+    match Connection::get_private(BusType::Session) {
+        Ok(conn) => send_notification(&conn, summary, body, icon, urgency, replaces_id, Vec::new()),
+        Err(err) => {
+            error!("Could not connect to the session bus: {:?}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::Write;
+use std::io;
+use std::sync::mpsc::{Receiver};
+
+use config::Config;
+
+// Text for one status item, identified by the item's id. An empty color
+// leaves the bar's default color.
+#[derive(Clone, Default, Debug)]
+pub struct Block {
+    pub id: String,
+    pub text: String,
+    pub color: String
+}
+
+// Status line protocol spoken on stdout
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    // One line of plain text per update
+    Plain,
+    // The i3bar JSON protocol, which supports colors
+    I3bar
+}
+
+impl Format {
+    fn from_config(config: &Config) -> Format {
+        match config.get_str("output", "format") {
+            Some("i3bar") => Format::I3bar,
+            Some("plain") | None => Format::Plain,
+            Some(format) => {
+                error!("Unknown output format: {}", format);
+                Format::Plain
+            }
+        }
+    }
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+fn format_plain(blocks: &[&Block]) -> String {
+    blocks.iter()
+        .map(|b| b.text.clone())
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+fn format_i3bar(blocks: &[&Block]) -> String {
+    let blocks = blocks.iter().map(|b| {
+        let mut json = format!("{{\"name\":\"{}\",\"full_text\":\"{}\"",
+            escape_json(&b.id), escape_json(&b.text));
+        if !b.color.is_empty() {
+            json.push_str(&format!(",\"color\":\"{}\"", escape_json(&b.color)));
+        }
+        json.push('}');
+        json
+    }).collect::<Vec<String>>();
+    format!("[{}],", blocks.join(","))
+}
+
+fn write_line(line: &str) {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    if let Err(err) = writeln!(handle, "{}", line).and_then(|_| handle.flush()) {
+        error!("Could not write status line: {:?}", err);
+    }
+}
+
+// Writes the status line read by swaybar's `status_command` to stdout.
+// Blocks keep the order in which they were first seen and empty blocks
+// are left out of the line.
+pub fn write_status_line(in_chan: Receiver<Block>, config: Config) {
+    let format = Format::from_config(&config);
+    if format == Format::I3bar {
+        write_line("{\"version\":1}");
+        write_line("[");
+    }
+    let mut blocks: Vec<Block> = Vec::new();
+    loop {
+        match in_chan.recv() {
+            Ok(block) => {
+                match blocks.iter().position(|b| b.id == block.id) {
+                    Some(index) => blocks[index] = block,
+                    None => blocks.push(block)
+                }
+                let shown: Vec<&Block> = blocks.iter()
+                    .filter(|b| !b.text.is_empty())
+                    .collect();
+                match format {
+                    Format::Plain => write_line(&format_plain(&shown)),
+                    Format::I3bar => write_line(&format_i3bar(&shown))
+                }
+            },
+            Err(_) => return
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use dbus::Connection;
+use dbus::BusType;
+use dbus::Error;
+use dbus::SignalArgs;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::thread;
+use tokio_core::reactor::Core;
+use futures::{Stream};
+use dbus_tokio::AConnection;
+use upower::OrgFreedesktopDBusProperties;
+use upower::OrgFreedesktopDBusPropertiesPropertiesChanged;
+use upower::OrgFreedesktopUPower;
+use upower::OrgFreedesktopUPowerDeviceAdded;
+use upower::OrgFreedesktopUPowerDeviceRemoved;
+use properties::PropertyCache;
+use output::Block;
+use status;
+use status::StatusUpdate;
+use std::sync::mpsc::{channel, Sender};
+
+// Type constants
+// https://upower.freedesktop.org/docs/Device.html#Device:Type
+const TYPE_MOUSE: u32 = 5;
+const TYPE_KEYBOARD: u32 = 6;
+const TYPE_GAMING_INPUT: u32 = 12;
+const TYPE_HEADSET: u32 = 17;
+
+// Percentage at or below which a peripheral is shown with a warning icon
+const LOW_PERCENTAGE: i64 = 15;
+
+// Device properties that affect a peripheral's item
+const RELEVANT_PROPERTIES: [&'static str; 3] = ["IsPresent", "Percentage", "Model"];
+
+fn is_peripheral_type(device_type: u32) -> bool {
+    match device_type {
+        TYPE_MOUSE | TYPE_KEYBOARD | TYPE_GAMING_INPUT | TYPE_HEADSET => true,
+        _ => false
+    }
+}
+
+struct Peripheral {
+    device_type: u32,
+    model: String,
+    percentage: i64
+}
+
+pub struct PeripheralMonitor {
+    conn: Rc<Connection>,
+    text_chan: Sender<Block>,
+    // Properties of the peripheral devices, signals for other devices such
+    // as the laptop battery are ignored
+    devices: RefCell<HashMap<String, PropertyCache>>
+}
+
+impl PeripheralMonitor {
+    pub fn new(text_chan: Sender<Block>) -> Result<PeripheralMonitor, Error> {
+        match Connection::get_private(BusType::System) {
+            Ok(c) => {
+                Ok(PeripheralMonitor {
+                    conn: Rc::new(c),
+                    text_chan: text_chan,
+                    devices: RefCell::new(HashMap::new())
+                })
+            },
+            Err(err) => {
+                Err(err)
+            }
+        }
+    }
+
+    fn get_devices(&self) -> Vec<String> {
+        let c_path = self.conn.with_path(
+            "org.freedesktop.UPower", "/org/freedesktop/UPower", 5000);
+        match c_path.enumerate_devices() {
+            Ok(devices) => devices.iter().map(|p| p.to_string()).collect(),
+            Err(err) => {
+                error!("Could not enumerate UPower devices: {:?}", err);
+                Vec::new()
+            }
+        }
+    }
+
+    // Loads all properties of the device at `path` into the cache when it
+    // is a peripheral
+    fn load_device(&self, path: &str) {
+        let c_path = self.conn.with_path("org.freedesktop.UPower", path, 5000);
+        let mut properties = PropertyCache::new();
+        match c_path.get_all("org.freedesktop.UPower.Device") {
+            Ok(all) => {
+                properties.update(all, &[]);
+            },
+            Err(err) => {
+                error!("Could not get properties of {}: {:?}", path, err);
+                return
+            }
+        }
+        if properties.get_u32("Type").map_or(false, is_peripheral_type) {
+            self.devices.borrow_mut().insert(path.to_string(), properties);
+        }
+    }
+
+    // Updates the cache of a known peripheral from a PropertiesChanged
+    // payload, returns whether its item needs to be updated
+    fn update_properties(&self, path: &str,
+        changed: OrgFreedesktopDBusPropertiesPropertiesChanged) -> bool {
+        if changed.interface_name != "org.freedesktop.UPower.Device" {
+            return false
+        }
+        let mut devices = self.devices.borrow_mut();
+        let properties = match devices.get_mut(path) {
+            Some(properties) => properties,
+            None => return false
+        };
+        let mut is_relevant = properties.update(changed.changed_properties, &RELEVANT_PROPERTIES);
+        if !changed.invalidated_properties.is_empty() {
+            let c_path = self.conn.with_path("org.freedesktop.UPower", path, 5000);
+            for name in changed.invalidated_properties.iter() {
+                match c_path.get("org.freedesktop.UPower.Device", name) {
+                    Ok(value) => properties.insert(name, value),
+                    Err(err) => error!("Could not get peripheral property {}: {:?}", name, err)
+                }
+                if RELEVANT_PROPERTIES.contains(&name.as_str()) {
+                    is_relevant = true;
+                }
+            }
+        }
+        is_relevant
+    }
+
+    fn get_peripheral(&self, path: &str) -> Option<Peripheral> {
+        let devices = self.devices.borrow();
+        let properties = match devices.get(path) {
+            Some(properties) => properties,
+            None => return None
+        };
+        // Disconnected peripherals are either removed by UPower or kept
+        // around as not present with no charge reported
+        if !properties.get_bool("IsPresent").unwrap_or(false) {
+            return None
+        }
+        let percentage = match properties.get_f64("Percentage") {
+            Some(percentage) if percentage > 0.0 => percentage.round() as i64,
+            _ => return None
+        };
+        Some(Peripheral {
+            device_type: properties.get_u32("Type").unwrap_or(0),
+            model: properties.get_str("Model").unwrap_or("").to_string(),
+            percentage: percentage
+        })
+    }
+
+    fn update_status(&self, peripheral: &Peripheral) -> StatusUpdate {
+        let mut update = StatusUpdate::new(self.get_icon(peripheral));
+        update.text = format!("{} {}%", peripheral.model, peripheral.percentage);
+        update.tooltip = format!("{}%", peripheral.percentage);
+        if peripheral.percentage <= LOW_PERCENTAGE {
+            update.tooltip = format!("{} - battery low", update.tooltip);
+        }
+        update
+    }
+
+    fn get_icon(&self, peripheral: &Peripheral) -> String {
+        if peripheral.percentage <= LOW_PERCENTAGE {
+            return String::from("battery-caution-symbolic")
+        }
+        match peripheral.device_type {
+            TYPE_MOUSE => return String::from("input-mouse-symbolic"),
+            TYPE_KEYBOARD => return String::from("input-keyboard-symbolic"),
+            TYPE_GAMING_INPUT => return String::from("input-gaming-symbolic"),
+            TYPE_HEADSET => return String::from("audio-headset-symbolic"),
+            _ => return String::from("battery-symbolic")
+        }
+    }
+
+    fn show_item(&self, path: &str, peripheral: &Peripheral) -> Sender<StatusUpdate> {
+        let (item_tx, item_rx) = channel();
+        let title = peripheral.model.clone();
+        let name = path.to_string();
+        let text_chan = self.text_chan.clone();
+        let notifier_text_chan = self.text_chan.clone();
+        thread::spawn(move || {
+            if let Ok(mut notifier) = status::StatusNotifier::new(&name, text_chan) {
+                info!("Starting peripheral notifier for {}", name);
+                notifier.set_title(title);
+                if let Err(err) = notifier.run(item_rx) {
+                    error!("Could not start peripheral notifier: {:?}", err)
+                }
+                info!("Stopped peripheral notifier for {}", name);
+                // Clear the text the item left on the status line
+                let block = Block {
+                    id: name.clone(),
+                    text: String::new(),
+                    color: String::new()
+                };
+                if let Err(err) = notifier_text_chan.send(block) {
+                    error!("Could not clear peripheral text: {:?}", err);
+                }
+            }
+        });
+        item_tx
+    }
+
+    // Shows, updates or hides the item for the device at `path`,
+    // depending on whether it is still a peripheral reporting a battery.
+    fn update_item(&self, items: &mut HashMap<String, Sender<StatusUpdate>>, path: &str) {
+        match self.get_peripheral(path) {
+            Some(peripheral) => {
+                if !items.contains_key(path) {
+                    let item_tx = self.show_item(path, &peripheral);
+                    items.insert(path.to_string(), item_tx);
+                }
+                let update = self.update_status(&peripheral);
+                let icon = update.icon_name.clone();
+                if let Some(item_tx) = items.get(path) {
+                    match item_tx.send(update) {
+                        Ok(_) => info!("Sent peripheral icon: {} - {}", path, icon),
+                        Err(err) => error!("Could not send peripheral icon: {} - {}", icon, err)
+                    }
+                }
+            },
+            None => {
+                // Dropping the sender stops the notifier thread, which
+                // unregisters the item from the watcher
+                if items.remove(path).is_some() {
+                    info!("Hiding peripheral: {}", path);
+                }
+            }
+        }
+    }
+
+    pub fn run(&self) {
+        let rules = [
+            "type=signal,sender=org.freedesktop.UPower,path=/org/freedesktop/UPower,member=DeviceAdded",
+            "type=signal,sender=org.freedesktop.UPower,path=/org/freedesktop/UPower,member=DeviceRemoved",
+            "type=signal,sender=org.freedesktop.UPower,path_namespace=/org/freedesktop/UPower/devices,member=PropertiesChanged"
+        ];
+        for rule in rules.iter() {
+            if let Err(err) = self.conn.add_match(rule) {
+                error!("Peripheral monitor could not add dbus match: {:?}", err);
+                return
+            }
+        }
+        let mut items: HashMap<String, Sender<StatusUpdate>> = HashMap::new();
+        for path in self.get_devices() {
+            self.load_device(&path);
+            self.update_item(&mut items, &path);
+        }
+        let mut core = Core::new().unwrap();
+        let aconn = AConnection::new(self.conn.clone(), core.handle()).unwrap();
+        let messages = aconn.messages().unwrap();
+        let signals = messages.for_each(|m| {
+            if let Some(added) = OrgFreedesktopUPowerDeviceAdded::from_message(&m) {
+                self.load_device(&added.device);
+                self.update_item(&mut items, &added.device);
+            } else if let Some(removed) = OrgFreedesktopUPowerDeviceRemoved::from_message(&m) {
+                self.devices.borrow_mut().remove(&removed.device.to_string());
+                if items.remove(&removed.device.to_string()).is_some() {
+                    info!("Peripheral removed: {}", removed.device);
+                }
+            } else if let Some(changed) = OrgFreedesktopDBusPropertiesPropertiesChanged::from_message(&m) {
+                let path = m.path().map(|path| path.to_string()).unwrap_or(String::new());
+                if self.update_properties(&path, changed) {
+                    self.update_item(&mut items, &path);
+                }
+            }
+            Ok(())
+        });
+        core.run(signals).unwrap();
+    }
+}
+
+pub fn monitor_peripherals(text_chan: Sender<Block>) {
+    match PeripheralMonitor::new(text_chan) {
+        Ok(monitor) => {
+            info!("Starting peripheral monitor");
+            monitor.run();
+        },
+        Err(err) => {
+            error!("Could not start peripheral monitor: {:?}", err);
+        }
+    }
+}
@@ -1,36 +1,170 @@
 use dbus::Connection;
 use dbus::BusType;
-use dbus::arg::RefArg;
 use dbus::Error;
+use dbus::SignalArgs;
 
 use std::rc::Rc;
 use tokio_core::reactor::Core;
 use futures::{Stream};
 use dbus_tokio::AConnection;
 use upower::OrgFreedesktopDBusProperties;
-use upower::OrgFreedesktopUPowerDevice;
+use upower::OrgFreedesktopDBusPropertiesPropertiesChanged;
+use upower::OrgFreedesktopUPowerDeviceAdded;
+use upower::OrgFreedesktopUPowerDeviceRemoved;
+use properties::PropertyCache;
+use status::StatusUpdate;
+use config::Config;
+use battery_health;
+use battery_health::BatteryHealth;
+use battery_history::{BatteryHistory, HistoryOutput};
+use charge_threshold;
+use charge_threshold::ChargeThresholds;
+use critical_action::CriticalAction;
+use line_power::LinePower;
+use low_battery::LowBatteryNotifier;
+use power_draw::PowerDraw;
+use std::cell::RefCell;
+use std::path::PathBuf;
 use std::sync::mpsc::{Sender};
 
 // State constants
 // https://upower.freedesktop.org/docs/Device.html#Device:State
-const UNKNOWN: i64 = 0;
-const CHARGING: i64 = 1;
-const DISCHARGING: i64 = 2;
-const EMPTY: i64 = 3;
-const FULLY_CHARGED: i64 = 4;
-const PENDING_CHARGE: i64 = 5;
-const PENDING_DISCHARGE: i64 = 6;
+pub const UNKNOWN: i64 = 0;
+pub const CHARGING: i64 = 1;
+pub const DISCHARGING: i64 = 2;
+pub const EMPTY: i64 = 3;
+pub const FULLY_CHARGED: i64 = 4;
+pub const PENDING_CHARGE: i64 = 5;
+pub const PENDING_DISCHARGE: i64 = 6;
+
+// WarningLevel constants
+// https://upower.freedesktop.org/docs/Device.html#Device:WarningLevel
+pub const WARNING_LEVEL_LOW: u32 = 3;
+pub const WARNING_LEVEL_CRITICAL: u32 = 4;
+pub const WARNING_LEVEL_ACTION: u32 = 5;
+
+// BatteryLevel constants, for devices that report coarse levels instead of
+// percentages
+// https://upower.freedesktop.org/docs/Device.html#Device:BatteryLevel
+pub const BATTERY_LEVEL_UNKNOWN: u32 = 0;
+pub const BATTERY_LEVEL_NONE: u32 = 1;
+pub const BATTERY_LEVEL_LOW: u32 = 3;
+pub const BATTERY_LEVEL_CRITICAL: u32 = 4;
+pub const BATTERY_LEVEL_NORMAL: u32 = 6;
+pub const BATTERY_LEVEL_HIGH: u32 = 7;
+pub const BATTERY_LEVEL_FULL: u32 = 8;
+
+// How the battery icon is chosen
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IconMode {
+    // Buckets of the battery percentage
+    Percentage,
+    // UPower's WarningLevel and BatteryLevel
+    Levels,
+    // UPower's IconName, falling back to Levels
+    UPowerIcon
+}
+
+impl IconMode {
+    fn from_config(config: &Config) -> IconMode {
+        match config.get_str("power", "icon_mode") {
+            Some("upower-levels") => IconMode::Levels,
+            Some("upower-icon") => IconMode::UPowerIcon,
+            Some("percentage") | None => IconMode::Percentage,
+            Some(mode) => {
+                error!("Unknown battery icon mode: {}", mode);
+                IconMode::Percentage
+            }
+        }
+    }
+}
+
+// DisplayDevice properties that affect the battery status
+const RELEVANT_PROPERTIES: [&'static str; 9] = [
+    "State", "Percentage", "TimeToEmpty", "TimeToFull", "WarningLevel",
+    "BatteryLevel", "IconName", "EnergyRate", "IsPresent"
+];
+
+// What to show on systems without a battery
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NoBattery {
+    // An AC adapter icon
+    Ac,
+    // Nothing, the item is hidden
+    Hide
+}
+
+impl NoBattery {
+    fn from_config(config: &Config) -> NoBattery {
+        match config.get_str("power", "no_battery") {
+            Some("hide") => NoBattery::Hide,
+            Some("ac") | None => NoBattery::Ac,
+            Some(no_battery) => {
+                error!("Unknown no_battery option: {}", no_battery);
+                NoBattery::Ac
+            }
+        }
+    }
+}
+
+// Weight of a new UPower time estimate in the smoothed estimate
+const TIME_SMOOTHING: f64 = 0.2;
+
+// Smoothed time to empty or full, reset whenever the battery state changes
+#[derive(Clone, Copy, Debug)]
+struct TimeEstimate {
+    state: i64,
+    seconds: f64
+}
 
 pub struct PowerMonitor {
-    conn: Rc<Connection>
+    conn: Rc<Connection>,
+    properties: RefCell<PropertyCache>,
+    line_power: RefCell<LinePower>,
+    no_battery: NoBattery,
+    time_estimate: RefCell<Option<TimeEstimate>>,
+    icon_mode: IconMode,
+    health: RefCell<Vec<BatteryHealth>>,
+    threshold_paths: RefCell<Vec<PathBuf>>,
+    health_warning_percentage: f64,
+    history: Option<BatteryHistory>,
+    power_draw: Option<PowerDraw>,
+    // Sparkline and the percentage it was fetched at
+    sparkline: RefCell<Option<(i64, Option<String>)>>,
+    low_battery: Option<LowBatteryNotifier>,
+    critical_action: Option<CriticalAction>
 }
 
 impl PowerMonitor {
-    pub fn new() -> Result<PowerMonitor, Error> {
+    pub fn new(config: &Config) -> Result<PowerMonitor, Error> {
+        let low_battery = if config.get_bool("power", "notifications").unwrap_or(true) {
+            match LowBatteryNotifier::new(config) {
+                Ok(notifier) => Some(notifier),
+                Err(err) => {
+                    error!("Could not start low battery notifications: {:?}", err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
         match Connection::get_private(BusType::System) {
             Ok(c) => { 
                 Ok(PowerMonitor {
-                    conn: Rc::new(c)
+                    conn: Rc::new(c),
+                    properties: RefCell::new(PropertyCache::new()),
+                    line_power: RefCell::new(LinePower::default()),
+                    no_battery: NoBattery::from_config(config),
+                    time_estimate: RefCell::new(None),
+                    icon_mode: IconMode::from_config(config),
+                    health: RefCell::new(Vec::new()),
+                    threshold_paths: RefCell::new(Vec::new()),
+                    health_warning_percentage: battery_health::get_warning_percentage(config),
+                    history: BatteryHistory::new(config),
+                    power_draw: PowerDraw::new(config),
+                    sparkline: RefCell::new(None),
+                    low_battery: low_battery,
+                    critical_action: CriticalAction::new(config)
                 })
             },
             Err(err) => {
@@ -38,88 +172,423 @@ impl PowerMonitor {
             }
         }
     }
-    fn get_state(&self) -> Option<i64> {
+    // Loads all DisplayDevice properties into the cache
+    fn load_properties(&self) {
         let c_path = self.conn.with_path(
-            "org.freedesktop.UPower", 
-            "/org/freedesktop/UPower/devices/DisplayDevice", 
-            5000);
-        if let Ok(variant) = c_path.get("org.freedesktop.UPower.Device", "State") {
-            return variant.as_i64();
-        } else {
-            return None
+            "org.freedesktop.UPower", "/org/freedesktop/UPower/devices/DisplayDevice", 5000);
+        match c_path.get_all("org.freedesktop.UPower.Device") {
+            Ok(all) => {
+                self.properties.borrow_mut().update(all, &[]);
+            },
+            Err(err) => error!("Could not get battery properties: {:?}", err)
+        }
+        *self.line_power.borrow_mut() = LinePower::load(&self.conn);
+        self.load_health();
+        *self.threshold_paths.borrow_mut() = charge_threshold::get_threshold_paths(&self.conn);
+        self.add_power_draw_sample();
+    }
+
+    // The DisplayDevice's EnergyRate is the sum over all batteries
+    fn add_power_draw_sample(&self) {
+        if let Some(ref power_draw) = self.power_draw {
+            let properties = self.properties.borrow();
+            if let (Some(state), Some(rate)) = (properties.get_i64("State"),
+                properties.get_f64("EnergyRate")) {
+                power_draw.add_sample(state, rate);
+            }
+        }
+    }
+
+    fn load_health(&self) {
+        let health = battery_health::get_battery_health(&self.conn);
+        for battery in health.iter() {
+            if battery.is_low(self.health_warning_percentage) {
+                warn!("Battery health is low: {} at {:.0}% of design capacity",
+                    battery.model, battery.capacity);
+            }
+        }
+        *self.health.borrow_mut() = health;
+    }
+
+    // Updates the cache from a PropertiesChanged payload, returns whether
+    // the battery status needs to be recomputed
+    fn update_properties(&self, changed: OrgFreedesktopDBusPropertiesPropertiesChanged) -> bool {
+        if changed.interface_name != "org.freedesktop.UPower.Device" {
+            return false
+        }
+        // Full energy is only updated after charging or discharging, no need
+        // to look at the batteries more often than the state changes
+        if changed.changed_properties.contains_key("State") {
+            self.load_health();
+        }
+        let rate_changed = changed.changed_properties.contains_key("EnergyRate");
+        let mut is_relevant = self.properties.borrow_mut().update(
+            changed.changed_properties, &RELEVANT_PROPERTIES);
+        if rate_changed {
+            self.add_power_draw_sample();
+        }
+        if !changed.invalidated_properties.is_empty() {
+            let c_path = self.conn.with_path(
+                "org.freedesktop.UPower", "/org/freedesktop/UPower/devices/DisplayDevice", 5000);
+            for name in changed.invalidated_properties.iter() {
+                match c_path.get("org.freedesktop.UPower.Device", name) {
+                    Ok(value) => self.properties.borrow_mut().insert(name, value),
+                    Err(err) => error!("Could not get battery property {}: {:?}", name, err)
+                }
+                if RELEVANT_PROPERTIES.contains(&name.as_str()) {
+                    is_relevant = true;
+                }
+            }
         }
+        is_relevant
+    }
+
+    // The DisplayDevice is not present on systems without a battery
+    fn has_battery(&self) -> bool {
+        self.properties.borrow().get_bool("IsPresent").unwrap_or(true)
+    }
+
+    // Read on every update since the thresholds can be changed at any time
+    fn get_charge_thresholds(&self) -> Option<ChargeThresholds> {
+        self.threshold_paths.borrow().first()
+            .map(|path| ChargeThresholds::read(path))
+    }
+
+    fn get_state(&self) -> Option<i64> {
+        self.properties.borrow().get_i64("State")
     }
 
     fn get_percentage(&self) -> Option<i64> {
-        let c_path = self.conn.with_path(
-        "org.freedesktop.UPower", "/org/freedesktop/UPower/devices/DisplayDevice", 5000);
-        match c_path.get_percentage() {
-            Ok(percentage) => {
-                return Some(percentage.round() as i64)
+        self.properties.borrow().get_f64("Percentage")
+            .map(|percentage| percentage.round() as i64)
+    }
+
+    fn get_warning_level(&self) -> Option<u32> {
+        self.properties.borrow().get_u32("WarningLevel")
+    }
+
+    fn get_battery_level(&self) -> Option<u32> {
+        self.properties.borrow().get_u32("BatteryLevel")
+    }
+
+    fn get_upower_icon_name(&self) -> Option<String> {
+        match self.properties.borrow().get_str("IconName") {
+            Some(icon_name) if !icon_name.is_empty() => Some(String::from(icon_name)),
+            _ => None
+        }
+    }
+
+    // Follows UPower's low battery policy: the warning level decides when
+    // the battery is low or critical, the battery level (or the percentage
+    // for devices that do not report levels) decides the other icons.
+    fn get_level_icon(&self, state: i64, percentage: i64,
+        warning_level: Option<u32>, battery_level: Option<u32>) -> String {
+        match state {
+            FULLY_CHARGED => return String::from("battery-full-charged-symbolic"),
+            EMPTY => return String::from("battery-empty-symbolic"),
+            PENDING_CHARGE | PENDING_DISCHARGE => return get_plugged_in_icon(percentage),
+            CHARGING | DISCHARGING => {},
+            _ => return String::from("battery-missing-symbolic")
+        }
+        let level = match warning_level {
+            Some(WARNING_LEVEL_CRITICAL) | Some(WARNING_LEVEL_ACTION) => "caution",
+            Some(WARNING_LEVEL_LOW) => "low",
+            _ => match battery_level {
+                Some(BATTERY_LEVEL_FULL) => "full",
+                Some(BATTERY_LEVEL_HIGH) => "good",
+                Some(BATTERY_LEVEL_NORMAL) => "medium",
+                Some(BATTERY_LEVEL_LOW) => "low",
+                Some(BATTERY_LEVEL_CRITICAL) => "caution",
+                _ => match percentage {
+                    98 ... 100 => "full",
+                    40 ... 97 => "good",
+                    _ => "medium"
+                }
+            }
+        };
+        match state {
+            CHARGING => format!("battery-{}-charging-symbolic", level),
+            _ => format!("battery-{}-symbolic", level)
+        }
+    }
+
+    fn get_icon(&self, state: i64, percentage: i64) -> String {
+        match state {
+            FULLY_CHARGED => {
+                return String::from("battery-full-charged-symbolic");
             },
-            Err(err) => {
-                error!("Could not get battery percentage: {:?}",
-                    err);
+            CHARGING => { 
+                match percentage {
+                    98 ... 100 => return String::from("battery-full-charging-symbolic"),
+                    40 ... 97 => return String::from("battery-good-charging-symbolic"),
+                    21 ... 39 => return String::from("battery-medium-charging-symbolic"),
+                    5 ... 20 => return String::from("battery-low-charging-symbolic"),
+                    0 ... 4 => return String::from("battery-caution-charging-symbolic"),
+                    _ => return String::from("battery-symbolic")
+                }
+            },
+            DISCHARGING => {
+                match percentage {
+                    98 ... 100 => return String::from("battery-full-charged-symbolic"),
+                    40 ... 97 => return String::from("battery-good-symbolic"),
+                    21 ... 39 => return String::from("battery-medium-symbolic"),
+                    5 ... 20 => return String::from("battery-low-symbolic"),
+                    0 ... 4 => return String::from("battery-caution-symbolic"),
+                    _ => return String::from("battery-symbolic")
+                }
+            },
+            EMPTY => {
+                return String::from("battery-empty-symbolic")
+            }
+            PENDING_DISCHARGE | PENDING_CHARGE => {
+                return get_plugged_in_icon(percentage)
+            }
+            UNKNOWN | _ => { 
+                return String::from("battery-missing-symbolic")
+            }
+        }
+    }
+
+    fn get_time(&self, state: i64) -> Option<i64> {
+        match state {
+            DISCHARGING => self.properties.borrow().get_i64("TimeToEmpty"),
+            CHARGING => self.properties.borrow().get_i64("TimeToFull"),
+            _ => None
+        }
+    }
+
+    // History only gains a point when the charge changes, so it is only
+    // fetched again when the percentage does
+    fn get_sparkline(&self, percentage: i64) -> Option<String> {
+        let history = match self.history {
+            Some(ref history) => history,
+            None => return None
+        };
+        if let Some((fetched_percentage, ref sparkline)) = *self.sparkline.borrow() {
+            if fetched_percentage == percentage {
+                return sparkline.clone()
+            }
+        }
+        let sparkline = battery_health::get_battery_paths(&self.conn).first()
+            .and_then(|path| history.get_sparkline(&self.conn, path));
+        *self.sparkline.borrow_mut() = Some((percentage, sparkline.clone()));
+        sparkline
+    }
+
+    // UPower recomputes its estimate on every update, smooth it so the
+    // displayed time does not jump around. An estimate of 0 means UPower
+    // does not know yet.
+    fn smooth_time(&self, state: i64, seconds: Option<i64>) -> Option<i64> {
+        let mut time_estimate = self.time_estimate.borrow_mut();
+        match seconds {
+            Some(seconds) if seconds > 0 => {
+                let smoothed = match *time_estimate {
+                    Some(estimate) if estimate.state == state => {
+                        estimate.seconds + TIME_SMOOTHING * (seconds as f64 - estimate.seconds)
+                    },
+                    _ => seconds as f64
+                };
+                *time_estimate = Some(TimeEstimate {
+                    state: state,
+                    seconds: smoothed
+                });
+                return Some(smoothed.round() as i64)
+            },
+            _ => {
+                *time_estimate = None;
                 return None
             }
         }
     }
 
-    pub fn update_status(&self) -> String {
+    fn update_no_battery_status(&self, ac_online: Option<bool>) -> StatusUpdate {
+        let mut update = StatusUpdate::new(String::from("ac-adapter-symbolic"));
+        match self.no_battery {
+            NoBattery::Hide => update.hidden = true,
+            NoBattery::Ac => {
+                update.text = String::from("AC");
+                update.tooltip = match ac_online {
+                    Some(false) => String::from("No battery, AC adapter offline"),
+                    _ => String::from("On AC power, no battery")
+                };
+            }
+        }
+        update
+    }
+
+    pub fn update_status(&self) -> StatusUpdate {
+        let ac_online = self.line_power.borrow().is_online();
+        if !self.has_battery() {
+            return self.update_no_battery_status(ac_online)
+        }
         if let Some(state) = self.get_state() {
+            // Batteries held at a charge threshold are reported as pending,
+            // or as unknown by some laptops, while AC is plugged in. Without
+            // line power information an unknown state stays unknown.
+            let state = match (state, ac_online) {
+                (UNKNOWN, Some(false)) => DISCHARGING,
+                (UNKNOWN, Some(true)) => PENDING_CHARGE,
+                _ => state
+            };
             if let Some(percentage) = self.get_percentage() {
-                match state {
-                    FULLY_CHARGED => {
-                        return String::from("battery-full-charged-symbolic");
-                    },
-                    CHARGING => { 
-                        match percentage {
-                            98 ... 100 => return String::from("battery-full-charging-symbolic"),
-                            40 ... 97 => return String::from("battery-good-charging-symbolic"),
-                            21 ... 39 => return String::from("battery-medium-charging-symbolic"),
-                            5 ... 20 => return String::from("battery-low-charging-symbolic"),
-                            0 ... 4 => return String::from("battery-caution-charging-symbolic"),
-                            _ => return String::from("battery-symbolic")
-                        }
+                let uses_levels = self.icon_mode != IconMode::Percentage;
+                let needs_warning_level = uses_levels || self.low_battery.as_ref()
+                    .map_or(false, |low_battery| low_battery.uses_warning_level());
+                let warning_level = if needs_warning_level {
+                    self.get_warning_level()
+                } else {
+                    None
+                };
+                let battery_level = if uses_levels {
+                    self.get_battery_level()
+                } else {
+                    None
+                };
+                if let Some(ref low_battery) = self.low_battery {
+                    low_battery.update(state, percentage, warning_level);
+                }
+                if let Some(ref critical_action) = self.critical_action {
+                    critical_action.update(state, percentage);
+                }
+                let icon = match self.icon_mode {
+                    IconMode::Percentage => self.get_icon(state, percentage),
+                    IconMode::Levels => self.get_level_icon(
+                        state, percentage, warning_level, battery_level),
+                    IconMode::UPowerIcon => match self.get_upower_icon_name() {
+                        Some(icon_name) => icon_name,
+                        None => self.get_level_icon(
+                            state, percentage, warning_level, battery_level)
+                    }
+                };
+                let mut update = StatusUpdate::new(icon);
+                let time = self.get_time(state);
+                let charge = match battery_level {
+                    Some(BATTERY_LEVEL_UNKNOWN) | Some(BATTERY_LEVEL_NONE) | None => {
+                        format!("{}%", percentage)
                     },
-                    DISCHARGING => {
-                        match percentage {
-                            98 ... 100 => return String::from("battery-full-charged-symbolic"),
-                            40 ... 97 => return String::from("battery-good-symbolic"),
-                            21 ... 39 => return String::from("battery-medium-symbolic"),
-                            5 ... 20 => return String::from("battery-low-symbolic"),
-                            0 ... 4 => return String::from("battery-caution-symbolic"),
-                            _ => return String::from("battery-symbolic")
+                    Some(level) => String::from(get_battery_level_description(level))
+                };
+                update.text = match state {
+                    PENDING_CHARGE | PENDING_DISCHARGE => format!("{} not charging", charge),
+                    _ => charge.clone()
+                };
+                update.tooltip = format!("{}, {}", charge, get_state_description(state));
+                if let Some(description) = self.get_charge_thresholds()
+                    .and_then(|thresholds| thresholds.description()) {
+                    update.tooltip = format!("{}\n{}", update.tooltip, description);
+                }
+                match ac_online {
+                    Some(true) => update.tooltip = format!("{}\nOn AC power", update.tooltip),
+                    Some(false) => update.tooltip = format!("{}\nOn battery power", update.tooltip),
+                    None => {}
+                }
+                if let Some(seconds) = self.smooth_time(state, time) {
+                    let time_text = format_time(state, seconds);
+                    update.text = format!("{} {}", update.text, time_text);
+                    update.tooltip = format!("{}\n{}", update.tooltip, time_text);
+                }
+                if let Some(ref power_draw) = self.power_draw {
+                    if let (Some(current), Some(average)) = (power_draw.get_current(),
+                        power_draw.get_average()) {
+                        update.text = format!("{} {:.1}W", update.text, current);
+                        update.color = power_draw.get_color();
+                        let label = match state {
+                            CHARGING => "Charge rate",
+                            _ => "Power draw"
+                        };
+                        update.tooltip = format!("{}\n{}: {:.1} W (average {:.1} W)",
+                            update.tooltip, label, current, average);
+                    }
+                }
+                if let Some(sparkline) = self.get_sparkline(percentage) {
+                    if let Some(ref history) = self.history {
+                        match history.output {
+                            HistoryOutput::Text => {
+                                update.text = format!("{} {}", update.text, sparkline);
+                            },
+                            HistoryOutput::Tooltip => {}
                         }
-                    },
-                    EMPTY => {
-                        return String::from("battery-empty-symbolic")
+                        update.tooltip = format!("{}\n{}: {}", update.tooltip,
+                            history.description(), sparkline);
                     }
-                    UNKNOWN | PENDING_DISCHARGE | PENDING_CHARGE | _ => { 
-                        return String::from("battery-missing-symbolic")
+                }
+                for battery in self.health.borrow().iter() {
+                    update.tooltip = format!("{}\n{}", update.tooltip, battery.summary());
+                    if battery.is_low(self.health_warning_percentage) {
+                        update.tooltip = format!("{}\nBattery health is low, consider replacing it",
+                            update.tooltip);
                     }
                 }
+                return update
+            }
+        }
+        return StatusUpdate::new(String::from("battery-symbolic"))
+    }
+
+    // Reloads the AC adapters when UPower adds or removes a device, moving
+    // the PropertiesChanged matches from the old adapters to the new ones.
+    // Returns whether the online state changed.
+    fn reload_line_power(&self) -> bool {
+        let previous = self.line_power.borrow().get_paths();
+        let online = self.line_power.borrow().is_online();
+        *self.line_power.borrow_mut() = LinePower::load(&self.conn);
+        self.watch_line_power(&previous);
+        self.line_power.borrow().is_online() != online
+    }
+
+    // Matches PropertiesChanged for the AC adapters that are not in
+    // `watched`, dropping the matches of the adapters that are gone
+    fn watch_line_power(&self, watched: &[String]) {
+        let paths = self.line_power.borrow().get_paths();
+        for path in watched.iter().filter(|path| !paths.contains(path)) {
+            if let Err(err) = self.conn.remove_match(&get_properties_match(path)) {
+                error!("Power monitor could not remove dbus match: {:?}", err);
+            }
+        }
+        for path in paths.iter().filter(|path| !watched.contains(path)) {
+            if let Err(err) = self.conn.add_match(&get_properties_match(path)) {
+                error!("Power monitor could not add dbus match: {:?}", err);
             }
         }
-        return String::from("battery-symbolic")
-        
     }
 
-    pub fn run(&self, out_chan: Sender<String>) {
+    pub fn run(&self, out_chan: Sender<StatusUpdate>) {
         self.conn.add_match(
             "type=signal,sender=org.freedesktop.UPower,path=/org/freedesktop/UPower/devices/DisplayDevice,member=PropertiesChanged").unwrap();
+        // AC adapters can show up after startup, e.g. with a USB-C dock
+        let rules = [
+            "type=signal,sender=org.freedesktop.UPower,path=/org/freedesktop/UPower,member=DeviceAdded",
+            "type=signal,sender=org.freedesktop.UPower,path=/org/freedesktop/UPower,member=DeviceRemoved"
+        ];
+        for rule in rules.iter() {
+            if let Err(err) = self.conn.add_match(rule) {
+                error!("Power monitor could not add dbus match: {:?}", err);
+            }
+        }
+        self.watch_line_power(&[]);
         let mut core = Core::new().unwrap();
         let aconn = AConnection::new(self.conn.clone(), core.handle()).unwrap();
         let messages = aconn.messages().unwrap();
         let signals = messages.for_each(|m| {
-            let headers = m.headers();
-            if let Some(member) = headers.3 {
-                if member == "PropertiesChanged" {
-                    let icon = self.update_status();
-                    match out_chan.send(icon.clone()) {
-                        Ok(_) => info!("Sent icon: {}", icon),
-                        Err(err) => error!("Could not send icon: {} - {}", icon, err)
-                    }
+            let needs_update = if OrgFreedesktopUPowerDeviceAdded::from_message(&m).is_some() ||
+                OrgFreedesktopUPowerDeviceRemoved::from_message(&m).is_some() {
+                self.reload_line_power()
+            } else if let Some(changed) = OrgFreedesktopDBusPropertiesPropertiesChanged::from_message(&m) {
+                let path = m.path().map(|path| path.to_string()).unwrap_or(String::new());
+                if path == "/org/freedesktop/UPower/devices/DisplayDevice" {
+                    self.update_properties(changed)
+                } else {
+                    self.line_power.borrow_mut().update(&path, &changed)
+                }
+            } else {
+                false
+            };
+            if needs_update {
+                let update = self.update_status();
+                let icon = update.icon_name.clone();
+                match out_chan.send(update) {
+                    Ok(_) => info!("Sent icon: {}", icon),
+                    Err(err) => error!("Could not send icon: {} - {}", icon, err)
                 }
             }
             Ok(())
@@ -128,12 +597,56 @@ impl PowerMonitor {
     }
 }
 
-pub fn monitor_power<'a>(out_chan: Sender<String>) {
-    match PowerMonitor::new() {
+fn get_properties_match(path: &str) -> String {
+    format!("type=signal,sender=org.freedesktop.UPower,path={},member=PropertiesChanged", path)
+}
+
+fn get_state_description(state: i64) -> &'static str {
+    match state {
+        CHARGING => "charging",
+        DISCHARGING => "discharging",
+        EMPTY => "empty",
+        FULLY_CHARGED => "fully charged",
+        PENDING_CHARGE | PENDING_DISCHARGE => "plugged in, not charging",
+        UNKNOWN | _ => "unknown"
+    }
+}
+
+// Adwaita's level icons come in steps of 10%
+fn get_plugged_in_icon(percentage: i64) -> String {
+    let level = ((percentage.max(0).min(100) + 5) / 10) * 10;
+    format!("battery-level-{}-plugged-in-symbolic", level)
+}
+
+fn get_battery_level_description(battery_level: u32) -> &'static str {
+    match battery_level {
+        BATTERY_LEVEL_LOW => "low",
+        BATTERY_LEVEL_CRITICAL => "critical",
+        BATTERY_LEVEL_NORMAL => "normal",
+        BATTERY_LEVEL_HIGH => "high",
+        BATTERY_LEVEL_FULL => "full",
+        _ => "unknown"
+    }
+}
+
+// Formats a time estimate as "1:42 left" or "0:35 to full"
+fn format_time(state: i64, seconds: i64) -> String {
+    let minutes = seconds / 60;
+    let time = format!("{}:{:02}", minutes / 60, minutes % 60);
+    match state {
+        CHARGING => format!("{} to full", time),
+        _ => format!("{} left", time)
+    }
+}
+
+pub fn monitor_power<'a>(out_chan: Sender<StatusUpdate>, config: Config) {
+    match PowerMonitor::new(&config) {
         Ok(monitor) => {
             info!("Starting power monitor");
-            let icon = monitor.update_status();
-            match out_chan.send(icon.clone()) {
+            monitor.load_properties();
+            let update = monitor.update_status();
+            let icon = update.icon_name.clone();
+            match out_chan.send(update) {
                 Ok(_) => info!("Sent initial icon: {}", icon.clone()),
                 Err(err) => error!("Could not send initial icon: {} - {}", icon, err)
             }
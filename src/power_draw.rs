@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use config::Config;
+
+const DEFAULT_AVERAGE_SAMPLES: i64 = 10;
+
+const WARNING_COLOR: &'static str = "#ffcc00";
+const CRITICAL_COLOR: &'static str = "#ff5555";
+
+// Rolling average of the battery's charge or discharge rate, fed from
+// UPower's EnergyRate updates. The samples are dropped whenever the battery
+// switches between charging and discharging.
+pub struct PowerDraw {
+    samples: RefCell<VecDeque<f64>>,
+    state: RefCell<Option<i64>>,
+    size: usize,
+    warning: Option<f64>,
+    critical: Option<f64>
+}
+
+impl PowerDraw {
+    pub fn new(config: &Config) -> Option<PowerDraw> {
+        if !config.get_bool("power", "power_draw").unwrap_or(false) {
+            return None
+        }
+        let size = config.get_i64("power", "power_draw_samples")
+            .unwrap_or(DEFAULT_AVERAGE_SAMPLES);
+        Some(PowerDraw {
+            samples: RefCell::new(VecDeque::new()),
+            state: RefCell::new(None),
+            size: size.max(1) as usize,
+            warning: config.get_i64("power", "power_draw_warning").map(|w| w as f64),
+            critical: config.get_i64("power", "power_draw_critical").map(|w| w as f64)
+        })
+    }
+
+    pub fn add_sample(&self, state: i64, rate: f64) {
+        let mut samples = self.samples.borrow_mut();
+        if *self.state.borrow() != Some(state) {
+            samples.clear();
+            *self.state.borrow_mut() = Some(state);
+        }
+        // UPower reports 0 while it has no estimate yet
+        if rate <= 0.0 {
+            return
+        }
+        if samples.len() == self.size {
+            samples.pop_front();
+        }
+        samples.push_back(rate);
+    }
+
+    pub fn get_current(&self) -> Option<f64> {
+        self.samples.borrow().back().cloned()
+    }
+
+    pub fn get_average(&self) -> Option<f64> {
+        let samples = self.samples.borrow();
+        if samples.is_empty() {
+            return None
+        }
+        Some(samples.iter().sum::<f64>() / samples.len() as f64)
+    }
+
+    // Color for the status line, based on the average so a short spike does
+    // not flash the bar
+    pub fn get_color(&self) -> String {
+        let average = match self.get_average() {
+            Some(average) => average,
+            None => return String::new()
+        };
+        match (self.warning, self.critical) {
+            (_, Some(critical)) if average >= critical => String::from(CRITICAL_COLOR),
+            (Some(warning), _) if average >= warning => String::from(WARNING_COLOR),
+            _ => String::new()
+        }
+    }
+}
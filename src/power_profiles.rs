@@ -0,0 +1,242 @@
+use dbus::Connection;
+use dbus::ConnPath;
+use dbus::BusType;
+use dbus::Error;
+use dbus::SignalArgs;
+use dbus::arg::RefArg;
+
+use power_profiles_daemon::NetHadessPowerProfiles;
+use power_profiles_daemon::OrgFreedesktopDBusProperties;
+use power_profiles_daemon::OrgFreedesktopDBusPropertiesPropertiesChanged;
+use properties::PropertyCache;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use tokio_core::reactor::Core;
+use futures::{Stream};
+use dbus_tokio::AConnection;
+
+use status::{Action, ActionHandler, StatusUpdate};
+use std::sync::mpsc::{Sender};
+
+// Profiles in the order they are cycled through
+// https://gitlab.freedesktop.org/upower/power-profiles-daemon
+const POWER_SAVER: &'static str = "power-saver";
+const BALANCED: &'static str = "balanced";
+const PERFORMANCE: &'static str = "performance";
+
+// power-profiles-daemon properties that affect the profile status
+const RELEVANT_PROPERTIES: [&'static str; 2] = ["ActiveProfile", "PerformanceDegraded"];
+
+fn get_profile_icon(profile: &str) -> String {
+    match profile {
+        POWER_SAVER => String::from("power-profile-power-saver-symbolic"),
+        PERFORMANCE => String::from("power-profile-performance-symbolic"),
+        _ => String::from("power-profile-balanced-symbolic")
+    }
+}
+
+fn get_profile_description(profile: &str) -> String {
+    match profile {
+        POWER_SAVER => String::from("Power saver"),
+        BALANCED => String::from("Balanced"),
+        PERFORMANCE => String::from("Performance"),
+        profile => String::from(profile)
+    }
+}
+
+fn get_degraded_description(reason: &str) -> String {
+    match reason {
+        "lap-detected" => String::from("computer is on a lap"),
+        "high-operating-temperature" => String::from("high temperature"),
+        reason => reason.replace("-", " ")
+    }
+}
+
+fn connect_power_profiles<'a>(conn: &'a Connection) -> ConnPath<'a, &'a Connection> {
+    conn.with_path("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles", 5000)
+}
+
+// Profiles offered by the daemon, performance is missing on hardware that
+// doesn't support it
+fn get_profiles(conn: &Connection) -> Vec<String> {
+    match connect_power_profiles(conn).get_profiles() {
+        Ok(profiles) => {
+            profiles.iter()
+                .filter_map(|profile| profile.get("Profile"))
+                .filter_map(|profile| profile.0.as_str().map(String::from))
+                .collect()
+        },
+        Err(err) => {
+            error!("Could not get power profiles: {:?}", err);
+            Vec::new()
+        }
+    }
+}
+
+// Switches to the next (step 1) or previous (step -1) profile
+fn cycle_profile(conn: &Connection, step: isize) {
+    let profiles = get_profiles(conn);
+    if profiles.is_empty() {
+        return
+    }
+    let c_path = connect_power_profiles(conn);
+    let active = match c_path.get_active_profile() {
+        Ok(active) => active,
+        Err(err) => {
+            error!("Could not get active power profile: {:?}", err);
+            return
+        }
+    };
+    let index = profiles.iter().position(|p| *p == active).unwrap_or(0) as isize;
+    let count = profiles.len() as isize;
+    let next = ((index + step + count) % count) as usize;
+    set_profile(conn, profiles[next].clone());
+}
+
+fn set_profile(conn: &Connection, profile: String) {
+    info!("Switching power profile to {}", profile);
+    if let Err(err) = connect_power_profiles(conn).set_active_profile(profile) {
+        error!("Could not set power profile: {:?}", err);
+    }
+}
+
+// Clicking the item cycles through the profiles, scrolling steps through
+// them and the secondary click goes back to balanced. The monitor picks up
+// the change from the daemon's PropertiesChanged signal.
+pub fn get_action_handler() -> Result<ActionHandler, Error> {
+    let conn = try!(Connection::get_private(BusType::System));
+    Ok(Box::new(move |action| {
+        match action {
+            Action::Activate | Action::ScrollUp => cycle_profile(&conn, 1),
+            Action::ScrollDown => cycle_profile(&conn, -1),
+            Action::SecondaryActivate => set_profile(&conn, String::from(BALANCED)),
+            Action::MenuItem(_) => {}
+        }
+    }))
+}
+
+pub struct PowerProfilesMonitor {
+    conn: Rc<Connection>,
+    properties: RefCell<PropertyCache>,
+    available: RefCell<bool>
+}
+
+impl PowerProfilesMonitor {
+    pub fn new() -> Result<PowerProfilesMonitor, Error> {
+        match Connection::get_private(BusType::System) {
+            Ok(c) => {
+                Ok(PowerProfilesMonitor {
+                    conn: Rc::new(c),
+                    properties: RefCell::new(PropertyCache::new()),
+                    available: RefCell::new(false)
+                })
+            },
+            Err(err) => {
+                Err(err)
+            }
+        }
+    }
+
+    // Loads all power-profiles-daemon properties into the cache
+    fn load_properties(&self) {
+        match connect_power_profiles(&self.conn).get_all("net.hadess.PowerProfiles") {
+            Ok(all) => {
+                self.properties.borrow_mut().update(all, &[]);
+                *self.available.borrow_mut() = true;
+            },
+            Err(err) => {
+                info!("Power profiles are not available: {:?}", err);
+                *self.available.borrow_mut() = false;
+            }
+        }
+    }
+
+    fn update_properties(&self, changed: OrgFreedesktopDBusPropertiesPropertiesChanged) -> bool {
+        if changed.interface_name != "net.hadess.PowerProfiles" {
+            return false
+        }
+        self.properties.borrow_mut().update(
+            changed.changed_properties, &RELEVANT_PROPERTIES)
+    }
+
+    // The item is hidden while power-profiles-daemon is not running
+    pub fn update_status(&self) -> StatusUpdate {
+        let properties = self.properties.borrow();
+        let profile = match (*self.available.borrow(), properties.get_str("ActiveProfile")) {
+            (true, Some(profile)) => profile,
+            _ => {
+                let mut update = StatusUpdate::new(get_profile_icon(BALANCED));
+                update.hidden = true;
+                return update
+            }
+        };
+        let mut update = StatusUpdate::new(get_profile_icon(profile));
+        update.text = get_profile_description(profile);
+        update.tooltip = format!("Power profile: {}", get_profile_description(profile));
+        match properties.get_str("PerformanceDegraded") {
+            Some(reason) if !reason.is_empty() => {
+                update.text = format!("{} (degraded)", update.text);
+                update.tooltip = format!("{}\nPerformance degraded: {}",
+                    update.tooltip, get_degraded_description(reason));
+            },
+            _ => {}
+        }
+        update
+    }
+
+    fn send_status(&self, out_chan: &Sender<StatusUpdate>) {
+        let update = self.update_status();
+        let icon = update.icon_name.clone();
+        match out_chan.send(update) {
+            Ok(_) => info!("Sent power profile icon: {}", icon),
+            Err(err) => error!("Could not send power profile icon: {} - {}", icon, err)
+        }
+    }
+
+    pub fn run(&self, out_chan: Sender<StatusUpdate>) {
+        if let Err(err) = self.conn.add_match(
+            "type=signal,sender=net.hadess.PowerProfiles,path=/net/hadess/PowerProfiles,member=PropertiesChanged")
+        {
+                error!("Power profiles monitor could not add dbus match: {:?}", err);
+                return
+        }
+        // The daemon is activated on demand and may also be restarted
+        if let Err(err) = self.conn.add_match(
+            "type=signal,sender=org.freedesktop.DBus,member=NameOwnerChanged,arg0=net.hadess.PowerProfiles")
+        {
+                error!("Power profiles monitor could not add dbus match: {:?}", err);
+                return
+        }
+        let mut core = Core::new().unwrap();
+        let aconn = AConnection::new(self.conn.clone(), core.handle()).unwrap();
+        let messages = aconn.messages().unwrap();
+        let signals = messages.for_each(|m| {
+            if let Some(changed) = OrgFreedesktopDBusPropertiesPropertiesChanged::from_message(&m) {
+                if self.update_properties(changed) {
+                    self.send_status(&out_chan);
+                }
+            } else if m.member().map(|member| &*member == "NameOwnerChanged").unwrap_or(false) {
+                *self.properties.borrow_mut() = PropertyCache::new();
+                self.load_properties();
+                self.send_status(&out_chan);
+            }
+            Ok(())
+        });
+        core.run(signals).unwrap();
+    }
+}
+
+pub fn monitor_power_profiles(out_chan: Sender<StatusUpdate>) {
+    match PowerProfilesMonitor::new() {
+        Ok(monitor) => {
+            info!("Starting power profiles monitor");
+            monitor.load_properties();
+            monitor.send_status(&out_chan);
+            monitor.run(out_chan);
+        },
+        Err(err) => {
+            error!("Could not start power profiles monitor: {:?}", err);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// This code was autogenerated with dbus-codegen-rust, see https://github.com/diwic/dbus-rs
+
+use dbus as dbus;
+use dbus::arg;
+
+pub trait OrgFreedesktopDBusProperties {
+    type Err;
+    fn get(&self, interface_name: &str, property_name: &str) -> Result<arg::Variant<Box<arg::RefArg>>, Self::Err>;
+    fn get_all(&self, interface_name: &str) -> Result<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>, Self::Err>;
+    fn set(&self, interface_name: &str, property_name: &str, value: arg::Variant<Box<arg::RefArg>>) -> Result<(), Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopDBusProperties for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn get(&self, interface_name: &str, property_name: &str) -> Result<arg::Variant<Box<arg::RefArg>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.DBus.Properties".into(), &"Get".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interface_name);
+            i.append(property_name);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let value: arg::Variant<Box<arg::RefArg>> = try!(i.read());
+        Ok(value)
+    }
+
+    fn get_all(&self, interface_name: &str) -> Result<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.DBus.Properties".into(), &"GetAll".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interface_name);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let properties: ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>> = try!(i.read());
+        Ok(properties)
+    }
+
+    fn set(&self, interface_name: &str, property_name: &str, value: arg::Variant<Box<arg::RefArg>>) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.DBus.Properties".into(), &"Set".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(interface_name);
+            i.append(property_name);
+            i.append(value);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopDBusPropertiesPropertiesChanged {
+    pub interface_name: String,
+    pub changed_properties: ::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>,
+    pub invalidated_properties: Vec<String>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopDBusPropertiesPropertiesChanged {
+    const NAME: &'static str = "PropertiesChanged";
+    const INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.interface_name as &arg::RefArg).append(i);
+        (&self.changed_properties as &arg::RefArg).append(i);
+        (&self.invalidated_properties as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.interface_name = try!(i.read());
+        self.changed_properties = try!(i.read());
+        self.invalidated_properties = try!(i.read());
+        Ok(())
+    }
+}
+
+pub trait NetHadessPowerProfiles {
+    type Err;
+    fn hold_profile(&self, profile: &str, reason: &str, application_id: &str) -> Result<u32, Self::Err>;
+    fn release_profile(&self, cookie: u32) -> Result<(), Self::Err>;
+    fn get_active_profile(&self) -> Result<String, Self::Err>;
+    fn set_active_profile(&self, value: String) -> Result<(), Self::Err>;
+    fn get_performance_inhibited(&self) -> Result<String, Self::Err>;
+    fn get_performance_degraded(&self) -> Result<String, Self::Err>;
+    fn get_profiles(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+    fn get_actions(&self) -> Result<Vec<String>, Self::Err>;
+    fn get_active_profile_holds(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> NetHadessPowerProfiles for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn hold_profile(&self, profile: &str, reason: &str, application_id: &str) -> Result<u32, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"net.hadess.PowerProfiles".into(), &"HoldProfile".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(profile);
+            i.append(reason);
+            i.append(application_id);
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let cookie: u32 = try!(i.read());
+        Ok(cookie)
+    }
+
+    fn release_profile(&self, cookie: u32) -> Result<(), Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"net.hadess.PowerProfiles".into(), &"ReleaseProfile".into(), |msg| {
+            let mut i = arg::IterAppend::new(msg);
+            i.append(cookie);
+        }));
+        try!(m.as_result());
+        Ok(())
+    }
+
+    fn get_active_profile(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "net.hadess.PowerProfiles", "ActiveProfile")
+    }
+
+    fn get_performance_inhibited(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "net.hadess.PowerProfiles", "PerformanceInhibited")
+    }
+
+    fn get_performance_degraded(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "net.hadess.PowerProfiles", "PerformanceDegraded")
+    }
+
+    fn get_profiles(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "net.hadess.PowerProfiles", "Profiles")
+    }
+
+    fn get_actions(&self) -> Result<Vec<String>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "net.hadess.PowerProfiles", "Actions")
+    }
+
+    fn get_active_profile_holds(&self) -> Result<Vec<::std::collections::HashMap<String, arg::Variant<Box<arg::RefArg>>>>, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "net.hadess.PowerProfiles", "ActiveProfileHolds")
+    }
+
+    fn set_active_profile(&self, value: String) -> Result<(), Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::set(&self, "net.hadess.PowerProfiles", "ActiveProfile", value)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NetHadessPowerProfilesProfileReleased {
+    pub cookie: u32,
+}
+
+impl dbus::SignalArgs for NetHadessPowerProfilesProfileReleased {
+    const NAME: &'static str = "ProfileReleased";
+    const INTERFACE: &'static str = "net.hadess.PowerProfiles";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.cookie as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.cookie = try!(i.read());
+        Ok(())
+    }
+}
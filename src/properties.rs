@@ -0,0 +1,68 @@
+use dbus::arg;
+use dbus::arg::{RefArg, Variant};
+
+use std::collections::HashMap;
+
+// Local copy of a D-Bus object's properties, kept up to date from
+// PropertiesChanged payloads so monitors don't have to query the object
+// again on every signal.
+#[derive(Debug, Default)]
+pub struct PropertyCache {
+    values: HashMap<String, Box<RefArg>>
+}
+
+impl PropertyCache {
+    pub fn new() -> PropertyCache {
+        PropertyCache {
+            values: HashMap::new()
+        }
+    }
+
+    // Stores the changed values and returns whether any of the `relevant`
+    // properties were among them
+    pub fn update(&mut self, changed: HashMap<String, Variant<Box<RefArg>>>,
+        relevant: &[&str]) -> bool {
+        let mut is_relevant = false;
+        for (name, value) in changed {
+            if relevant.contains(&name.as_str()) {
+                is_relevant = true;
+            }
+            self.values.insert(name, value.0);
+        }
+        is_relevant
+    }
+
+    pub fn insert(&mut self, name: &str, value: Variant<Box<RefArg>>) {
+        self.values.insert(name.to_string(), value.0);
+    }
+
+    pub fn get_i64(&self, name: &str) -> Option<i64> {
+        self.values.get(name).and_then(|value| value.as_i64())
+    }
+
+    pub fn get_u32(&self, name: &str) -> Option<u32> {
+        self.get_i64(name).map(|value| value as u32)
+    }
+
+    pub fn get_f64(&self, name: &str) -> Option<f64> {
+        self.values.get(name)
+            .and_then(|value| arg::cast::<f64>(&**value))
+            .map(|value| *value)
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.values.get(name)
+            .and_then(|value| arg::cast::<bool>(&**value))
+            .map(|value| *value)
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.values.get(name).and_then(|value| value.as_str())
+    }
+
+    pub fn get_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        self.values.get(name)
+            .and_then(|value| value.as_iter())
+            .map(|bytes| bytes.filter_map(|byte| byte.as_i64()).map(|byte| byte as u8).collect())
+    }
+}
@@ -0,0 +1,171 @@
+use dbus::Connection;
+
+use menu::MenuItem;
+use network::NM_DEVICE_TYPE_MODEM;
+use network_manager::OrgFreedesktopDBusProperties;
+use network_manager::OrgFreedesktopNetworkManager;
+use network_manager::OrgFreedesktopNetworkManagerDevice;
+use properties::PropertyCache;
+
+// Menu actions of the network item
+pub const ACTION_TOGGLE_AIRPLANE_MODE: &'static str = "toggle-airplane-mode";
+pub const ACTION_TOGGLE_WIFI: &'static str = "toggle-wifi";
+pub const ACTION_TOGGLE_WWAN: &'static str = "toggle-wwan";
+
+// NetworkManager properties that affect the radio status
+pub const RADIO_PROPERTIES: [&'static str; 4] = [
+    "WirelessEnabled", "WirelessHardwareEnabled", "WwanEnabled", "WwanHardwareEnabled"
+];
+
+// State of the Wi-Fi and mobile broadband radios. A radio is on when it is
+// both enabled in software and not blocked by a hardware kill switch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Radios {
+    pub wireless_enabled: bool,
+    pub wireless_hardware_enabled: bool,
+    pub wwan_enabled: bool,
+    pub wwan_hardware_enabled: bool,
+    // Mobile broadband is only offered when there is a modem
+    pub has_modem: bool
+}
+
+impl Radios {
+    pub fn from_properties(properties: &PropertyCache, has_modem: bool) -> Radios {
+        Radios {
+            wireless_enabled: properties.get_bool("WirelessEnabled").unwrap_or(true),
+            wireless_hardware_enabled: properties.get_bool("WirelessHardwareEnabled").unwrap_or(true),
+            wwan_enabled: properties.get_bool("WwanEnabled").unwrap_or(true),
+            wwan_hardware_enabled: properties.get_bool("WwanHardwareEnabled").unwrap_or(true),
+            has_modem: has_modem
+        }
+    }
+
+    // Asks NetworkManager, for the action handler which has no cache
+    pub fn load(conn: &Connection) -> Radios {
+        let c_path = conn.with_path(
+            "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+        let mut properties = PropertyCache::new();
+        match c_path.get_all("org.freedesktop.NetworkManager") {
+            Ok(all) => {
+                properties.update(all, &[]);
+            },
+            Err(err) => error!("Could not get radio state: {:?}", err)
+        }
+        let has_modem = OrgFreedesktopNetworkManager::get_devices(&c_path)
+            .unwrap_or(Vec::new())
+            .into_iter()
+            .any(|device| {
+                let d_path = conn.with_path("org.freedesktop.NetworkManager", device, 5000);
+                d_path.get_device_type().ok() == Some(NM_DEVICE_TYPE_MODEM)
+            });
+        Radios::from_properties(&properties, has_modem)
+    }
+
+    fn is_wireless_on(&self) -> bool {
+        self.wireless_enabled && self.wireless_hardware_enabled
+    }
+
+    fn is_wwan_on(&self) -> bool {
+        self.has_modem && self.wwan_enabled && self.wwan_hardware_enabled
+    }
+
+    // All radios are off, whether from the kill switch or from software
+    pub fn is_airplane_mode(&self) -> bool {
+        !self.is_wireless_on() && !self.is_wwan_on()
+    }
+
+    // The hardware kill switch blocks Wi-Fi, it can't be turned back on
+    // from software
+    pub fn is_hardware_blocked(&self) -> bool {
+        !self.wireless_hardware_enabled
+    }
+
+    // Tooltip line, None while Wi-Fi is on
+    pub fn description(&self) -> Option<String> {
+        if self.is_hardware_blocked() {
+            Some(String::from("Airplane mode (hardware switch)"))
+        } else if self.is_airplane_mode() {
+            Some(String::from("Airplane mode"))
+        } else if !self.is_wireless_on() {
+            Some(String::from("Wi-Fi off"))
+        } else {
+            None
+        }
+    }
+
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        let mut items = Vec::new();
+        let mut airplane_mode = MenuItem::new(String::from("Airplane mode"));
+        let mut wireless = MenuItem::new(String::from("Wi-Fi"));
+        if self.is_hardware_blocked() {
+            airplane_mode.label = String::from("Airplane mode (hardware switch)");
+            wireless.label = String::from("Wi-Fi (hardware switch off)");
+        } else {
+            airplane_mode.action = Some(String::from(ACTION_TOGGLE_AIRPLANE_MODE));
+            wireless.action = Some(String::from(ACTION_TOGGLE_WIFI));
+        }
+        airplane_mode.checked = Some(self.is_airplane_mode());
+        wireless.checked = Some(self.is_wireless_on());
+        items.push(airplane_mode);
+        items.push(wireless);
+        if self.has_modem {
+            let mut wwan = MenuItem::new(String::from("Mobile broadband"));
+            if self.wwan_hardware_enabled {
+                wwan.action = Some(String::from(ACTION_TOGGLE_WWAN));
+            } else {
+                wwan.label = String::from("Mobile broadband (hardware switch off)");
+            }
+            wwan.checked = Some(self.is_wwan_on());
+            items.push(wwan);
+        }
+        items
+    }
+}
+
+fn set_wireless_enabled(conn: &Connection, enabled: bool) {
+    info!("Turning Wi-Fi {}", if enabled { "on" } else { "off" });
+    let c_path = conn.with_path(
+        "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+    if let Err(err) = c_path.set_wireless_enabled(enabled) {
+        error!("Could not set Wi-Fi state: {:?}", err);
+    }
+}
+
+fn set_wwan_enabled(conn: &Connection, enabled: bool) {
+    info!("Turning mobile broadband {}", if enabled { "on" } else { "off" });
+    let c_path = conn.with_path(
+        "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+    if let Err(err) = c_path.set_wwan_enabled(enabled) {
+        error!("Could not set mobile broadband state: {:?}", err);
+    }
+}
+
+// Leaving airplane mode turns the radios back on, mobile broadband only
+// when there is a modem, as airplane mode ignores it otherwise
+pub fn toggle_airplane_mode(conn: &Connection) {
+    let radios = Radios::load(conn);
+    let enabled = radios.is_airplane_mode();
+    set_wireless_enabled(conn, enabled);
+    if radios.has_modem {
+        set_wwan_enabled(conn, enabled);
+    }
+}
+
+// Runs a radio menu action, returns false for other actions
+pub fn run_action(conn: &Connection, action: &str) -> bool {
+    let c_path = conn.with_path(
+        "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+    match action {
+        ACTION_TOGGLE_AIRPLANE_MODE => toggle_airplane_mode(conn),
+        ACTION_TOGGLE_WIFI => match c_path.get_wireless_enabled() {
+            Ok(enabled) => set_wireless_enabled(conn, !enabled),
+            Err(err) => error!("Could not get Wi-Fi state: {:?}", err)
+        },
+        ACTION_TOGGLE_WWAN => match c_path.get_wwan_enabled() {
+            Ok(enabled) => set_wwan_enabled(conn, !enabled),
+            Err(err) => error!("Could not get mobile broadband state: {:?}", err)
+        },
+        _ => return false
+    }
+    true
+}
@@ -0,0 +1,199 @@
+use dbus::Connection;
+use dbus::ConnPath;
+use dbus::BusType;
+use dbus::Path;
+
+use std::thread;
+
+use active_connection;
+use active_connection::ActiveConnection;
+use menu::MenuItem;
+use network_manager::OrgFreedesktopNetworkManager;
+use network_manager::OrgFreedesktopNetworkManagerSettings;
+use network_manager::OrgFreedesktopNetworkManagerSettingsConnection;
+
+// Menu actions of the network item, followed by the path of the saved
+// connection to bring up or of the active connection to bring down
+const ACTION_UP: &'static str = "connection-up";
+const ACTION_DOWN: &'static str = "connection-down";
+
+// Profiles that can't be brought up on their own
+const HIDDEN_TYPES: [&'static str; 1] = ["loopback"];
+
+// A connection profile saved in NetworkManager's settings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SavedConnection {
+    pub path: String,
+    pub name: String,
+    pub uuid: String,
+    pub connection_type: String,
+    pub vpn: bool
+}
+
+impl SavedConnection {
+    fn load(conn: &Connection, path: &str) -> Option<SavedConnection> {
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", path, 5000);
+        let settings = match c_path.get_settings() {
+            Ok(settings) => settings,
+            // The connection may already be gone
+            Err(_) => return None
+        };
+        let connection = match settings.get("connection") {
+            Some(connection) => connection,
+            None => return None
+        };
+        let get_str = |name: &str| connection.get(name)
+            .and_then(|value| value.0.as_str())
+            .map(String::from)
+            .unwrap_or(String::new());
+        let connection_type = get_str("type");
+        // Ports of bridges and bonds come up with their controller
+        if HIDDEN_TYPES.contains(&connection_type.as_str()) || connection.contains_key("master") {
+            return None
+        }
+        Some(SavedConnection {
+            path: String::from(path),
+            name: get_str("id"),
+            uuid: get_str("uuid"),
+            vpn: active_connection::is_vpn_type(&connection_type),
+            connection_type: connection_type
+        })
+    }
+
+    fn get_active<'a>(&self, active_connections: &'a [ActiveConnection]) -> Option<&'a ActiveConnection> {
+        active_connections.iter().find(|active| active.uuid == self.uuid)
+    }
+}
+
+// Saved connections sorted by name, VPNs last
+pub fn load_saved_connections(conn: &Connection) -> Vec<SavedConnection> {
+    let c_path = conn.with_path(
+        "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager/Settings", 5000);
+    let mut connections: Vec<SavedConnection> = match c_path.list_connections() {
+        Ok(paths) => {
+            paths.iter()
+                .filter_map(|path| SavedConnection::load(conn, path))
+                .collect()
+        },
+        Err(err) => {
+            error!("Could not get saved connections: {:?}", err);
+            Vec::new()
+        }
+    };
+    connections.sort_by(|a, b| a.vpn.cmp(&b.vpn).then(a.name.cmp(&b.name)));
+    connections
+}
+
+fn get_action(connection: &SavedConnection, active_connections: &[ActiveConnection]) -> String {
+    match connection.get_active(active_connections) {
+        Some(active) => format!("{} {}", ACTION_DOWN, active.path),
+        None => format!("{} {}", ACTION_UP, connection.path)
+    }
+}
+
+// "Connections" submenu, active connections are checked and picking one
+// brings it down, picking any other brings it up
+pub fn menu_item(connections: &[SavedConnection],
+    active_connections: &[ActiveConnection]) -> Option<MenuItem> {
+    if connections.is_empty() {
+        return None
+    }
+    let mut items = Vec::new();
+    for (index, connection) in connections.iter().enumerate() {
+        if index > 0 && connection.vpn && !connections[index - 1].vpn {
+            items.push(MenuItem::separator());
+        }
+        let mut item = MenuItem::with_action(connection.name.clone(),
+            get_action(connection, active_connections));
+        item.checked = Some(connection.get_active(active_connections).is_some());
+        items.push(item);
+    }
+    Some(MenuItem::submenu(String::from("Connections"), items))
+}
+
+fn connect_network_manager<'a>(conn: &'a Connection) -> ConnPath<'a, &'a Connection> {
+    // Activation returns once NetworkManager has started it, which can
+    // involve asking the user's agent for secrets
+    conn.with_path("org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 30000)
+}
+
+// Activation can take as long as the timeout, so it runs on its own thread
+// and connection rather than blocking the item's dbus thread
+pub fn spawn<F>(f: F) where F: FnOnce(&Connection) + Send + 'static {
+    thread::spawn(move || {
+        match Connection::get_private(BusType::System) {
+            Ok(conn) => f(&conn),
+            Err(err) => error!("Could not connect to the system bus: {:?}", err)
+        }
+    });
+}
+
+fn bring_up(conn: &Connection, path: &str) {
+    let path = match Path::new(path) {
+        Ok(path) => path,
+        Err(_) => {
+            error!("Invalid saved connection: {}", path);
+            return
+        }
+    };
+    info!("Activating connection {}", path);
+    // NetworkManager picks the device when none is given
+    if let Err(err) = connect_network_manager(conn).activate_connection(
+        path, Path::from("/"), Path::from("/")) {
+        error!("Could not activate connection: {:?}", err);
+    }
+}
+
+fn bring_down(conn: &Connection, path: &str) {
+    let path = match Path::new(path) {
+        Ok(path) => path,
+        Err(_) => {
+            error!("Invalid active connection: {}", path);
+            return
+        }
+    };
+    info!("Deactivating connection {}", path);
+    if let Err(err) = connect_network_manager(conn).deactivate_connection(path) {
+        error!("Could not deactivate connection: {:?}", err);
+    }
+}
+
+// Brings the saved connection with the given name or UUID up, or down when
+// it is active
+pub fn toggle(name: &str) {
+    let name = name.to_string();
+    spawn(move |conn| toggle_connection(conn, &name));
+}
+
+fn toggle_connection(conn: &Connection, name: &str) {
+    let connections = load_saved_connections(conn);
+    let connection = match connections.iter().find(|c| c.name == name || c.uuid == name) {
+        Some(connection) => connection,
+        None => {
+            error!("No saved connection named {}", name);
+            return
+        }
+    };
+    let active_connections = active_connection::load_active_connections(conn);
+    match connection.get_active(&active_connections) {
+        Some(active) => bring_down(conn, &active.path),
+        None => bring_up(conn, &connection.path)
+    }
+}
+
+// Runs a saved connection menu action, returns false for other actions
+pub fn run_action(action: &str) -> bool {
+    let words: Vec<&str> = action.split(' ').collect();
+    match words.as_slice() {
+        [ACTION_UP, path] => {
+            let path = path.to_string();
+            spawn(move |conn| bring_up(conn, &path))
+        },
+        [ACTION_DOWN, path] => {
+            let path = path.to_string();
+            spawn(move |conn| bring_down(conn, &path))
+        },
+        _ => return false
+    }
+    true
+}
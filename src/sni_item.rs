@@ -19,6 +19,7 @@ pub trait OrgKdeStatusNotifierItem {
     fn get_title(&self) -> Result<String, Self::Err>;
     fn get_icon_theme_path(&self) -> Result<String, Self::Err>;
     fn get_menu(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_tool_tip(&self) -> Result<(String, Vec<(i32, i32, Vec<u8>)>, String, String), Self::Err>;
 }
 
 impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgKdeStatusNotifierItem for dbus::ConnPath<'a, C> {
@@ -89,6 +90,10 @@ impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgKdeStatusNotifierItem
     fn get_menu(&self) -> Result<dbus::Path<'static>, Self::Err> {
         <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.kde.StatusNotifierItem", "Menu")
     }
+
+    fn get_tool_tip(&self) -> Result<(String, Vec<(i32, i32, Vec<u8>)>, String, String), Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.kde.StatusNotifierItem", "ToolTip")
+    }
 }
 
 pub fn org_kde_status_notifier_item_server<F, T, D>(factory: &tree::Factory<tree::MTFn<D>, D>, data: D::Interface, f: F) -> tree::Interface<tree::MTFn<D>, D>
@@ -244,6 +249,17 @@ where
         Ok(())
     });
     let i = i.add_p(p);
+
+    let p = factory.property::<(&str, Vec<(i32, i32, Vec<u8>)>, &str, &str), _>("ToolTip", Default::default());
+    let p = p.access(tree::Access::Read);
+    let fclone = f.clone();
+    let p = p.on_get(move |a, pinfo| {
+        let minfo = pinfo.to_method_info();
+        let d = fclone(&minfo);
+        a.append(try!(d.get_tool_tip()));
+        Ok(())
+    });
+    let i = i.add_p(p);
     i
 }
 
@@ -322,3 +338,17 @@ impl dbus::SignalArgs for OrgKdeStatusNotifierItemNewTitle {
         Ok(())
     }
 }
+
+#[derive(Debug, Default)]
+pub struct OrgKdeStatusNotifierItemNewToolTip {
+}
+
+impl dbus::SignalArgs for OrgKdeStatusNotifierItemNewToolTip {
+    const NAME: &'static str = "NewToolTip";
+    const INTERFACE: &'static str = "org.kde.StatusNotifierItem";
+    fn append(&self, _: &mut arg::IterAppend) {
+    }
+    fn get(&mut self, _: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        Ok(())
+    }
+}
@@ -1,8 +1,16 @@
 use std::rc::Rc;
 use std::cell::RefCell;
-use std::sync::mpsc::{Receiver};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use std::thread;
 
 use dbus;
+use dbus::arg::{RefArg, Variant};
 use dbus::Connection;
 use dbus::BusType;
 use dbus::tree::DataType;
@@ -12,15 +20,93 @@ use dbus::Member;
 use dbus::Message;
 use dbus::tree::MethodErr;
 use dbus::tree::Factory;
+use libc;
 
 use sni_watcher::OrgFreedesktopStatusNotifierWatcher;
 use sni_item::org_kde_status_notifier_item_server;
 use sni_item::OrgKdeStatusNotifierItem;
+use dbus_menu::com_canonical_dbusmenu_server;
+use dbus_menu::ComCanonicalDbusmenu;
+use menu;
+use menu::{Layout, MenuIds, MenuItem};
+use output::Block;
 
-
+// Update sent by a monitor to its StatusNotifier. The text and its color
+// go to the status line, the tooltip to the item's ToolTip description.
+// Hidden items are marked Passive, which hosts leave out of the tray.
 #[derive(Clone, Default, Debug)]
+pub struct StatusUpdate {
+    pub icon_name: String,
+    pub text: String,
+    pub color: String,
+    pub tooltip: String,
+    pub hidden: bool,
+    pub menu: Vec<MenuItem>
+}
+
+impl StatusUpdate {
+    pub fn new(icon_name: String) -> StatusUpdate {
+        StatusUpdate {
+            icon_name: icon_name,
+            text: String::new(),
+            color: String::new(),
+            tooltip: String::new(),
+            hidden: false,
+            menu: Vec::new()
+        }
+    }
+}
+
+// Click or scroll on a status item, or a click on one of its menu entries
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Activate,
+    SecondaryActivate,
+    ScrollUp,
+    ScrollDown,
+    MenuItem(String)
+}
+
+// Called from the item's notifier thread, so it has to do its own dbus calls
+// rather than talk to the monitor
+pub type ActionHandler = Box<Fn(Action)>;
+
 struct Status {
-    icon_name: RefCell<String>
+    id: String,
+    icon_name: RefCell<String>,
+    title: RefCell<String>,
+    tooltip: RefCell<String>,
+    status: RefCell<String>,
+    menu: RefCell<Vec<MenuItem>>,
+    menu_ids: RefCell<MenuIds>,
+    // Bumped every time the menu changes
+    menu_revision: RefCell<u32>,
+    // Whether the item ever had a menu, hosts only read the Menu path once
+    has_menu: RefCell<bool>,
+    action_handler: RefCell<Option<ActionHandler>>
+}
+
+// The action handler can't be printed, dbus only needs the rest
+impl fmt::Debug for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Status")
+            .field("id", &self.id)
+            .field("icon_name", &self.icon_name)
+            .field("title", &self.title)
+            .field("tooltip", &self.tooltip)
+            .field("status", &self.status)
+            .field("menu", &self.menu)
+            .finish()
+    }
+}
+
+impl Status {
+    fn handle_action(&self, action: Action) {
+        info!("{} item action: {:?}", self.id, action);
+        if let Some(ref handler) = *self.action_handler.borrow() {
+            handler(action);
+        }
+    }
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -29,16 +115,27 @@ struct StatusData;
 #[allow(unused)]
 impl OrgKdeStatusNotifierItem for Rc<Status> {  
    type Err = MethodErr;
-    fn scroll(&self, delta: i32, orientation: &str) -> Result<(), Self::Err> { Ok(()) }
+    fn scroll(&self, delta: i32, orientation: &str) -> Result<(), Self::Err> {
+        if orientation == "vertical" && delta != 0 {
+            self.handle_action(if delta < 0 { Action::ScrollUp } else { Action::ScrollDown });
+        }
+        Ok(())
+    }
     fn activate(&self,  x: i32, y: i32) -> Result<(), Self::Err> { 
+        self.handle_action(Action::Activate);
         Ok(()) 
     }
-    fn secondary_activate(&self,  x: i32, y: i32) -> Result<(), Self::Err> { Ok(()) }
+    fn secondary_activate(&self,  x: i32, y: i32) -> Result<(), Self::Err> {
+        self.handle_action(Action::SecondaryActivate);
+        Ok(())
+    }
     fn get_id(&self) -> Result<String, Self::Err> { 
-        Ok("".into()) }
+        Ok(self.id.clone())
+    }
     fn get_category(&self) -> Result<String, Self::Err> { Ok("I feel so introspected right now".into()) }
     fn get_status(&self) -> Result<String, Self::Err> { 
-        Ok("".into()) }
+        Ok(self.status.borrow().clone())
+    }
     fn get_icon_name(&self) -> Result<String, Self::Err> { 
         Ok(self.icon_name.borrow().clone())
     }
@@ -49,10 +146,70 @@ impl OrgKdeStatusNotifierItem for Rc<Status> {
     }
     fn get_attention_icon_name(&self) -> Result<String, Self::Err> { Ok("I feel so introspected right now".into()) }
     fn get_title(&self) -> Result<String, Self::Err> { 
-        Ok("".into()) }
+        Ok(self.title.borrow().clone())
+    }
     fn get_icon_theme_path(&self) -> Result<String, Self::Err> {
         Ok("".into()) }
-    fn get_menu(&self) -> Result<Path<'static>, Self::Err> { Ok("I feel so introspected right now".into()) }
+    fn get_menu(&self) -> Result<Path<'static>, Self::Err> {
+        // "/" tells hosts there is no menu, so they don't show an empty one
+        if *self.has_menu.borrow() { Ok("/MenuBar".into()) } else { Ok("/".into()) }
+    }
+    fn get_tool_tip(&self) -> Result<(String, Vec<(i32, i32, Vec<u8>)>, String, String), Self::Err> {
+        Ok((self.icon_name.borrow().clone(),
+            Vec::new(),
+            self.title.borrow().clone(),
+            self.tooltip.borrow().clone()))
+    }
+}
+
+#[allow(unused)]
+impl ComCanonicalDbusmenu for Rc<Status> {
+    type Err = MethodErr;
+    fn get_layout(&self, parent_id: i32, recursion_depth: i32, property_names: Vec<&str>) -> Result<(u32, Layout), Self::Err> {
+        match menu::get_layout(&self.menu.borrow(), &self.menu_ids.borrow(), parent_id, recursion_depth, &property_names) {
+            Some(layout) => Ok((*self.menu_revision.borrow(), layout)),
+            None => Err(MethodErr::invalid_arg(&parent_id))
+        }
+    }
+    fn get_group_properties(&self, ids: Vec<i32>, property_names: Vec<&str>) -> Result<Vec<(i32, HashMap<String, Variant<Box<RefArg>>>)>, Self::Err> {
+        let menu = self.menu.borrow();
+        let menu_ids = self.menu_ids.borrow();
+        Ok(ids.iter()
+            .filter_map(|id| menu::get_properties(&menu, &menu_ids, *id, &property_names)
+                .map(|properties| (*id, properties)))
+            .collect())
+    }
+    fn get_property(&self, id: i32, name: &str) -> Result<Variant<Box<RefArg>>, Self::Err> {
+        menu::get_properties(&self.menu.borrow(), &self.menu_ids.borrow(), id, &[name])
+            .and_then(|mut properties| properties.remove(name))
+            .ok_or(MethodErr::invalid_arg(&name))
+    }
+    fn event(&self, id: i32, event_id: &str, data: Variant<Box<RefArg>>, timestamp: u32) -> Result<(), Self::Err> {
+        if event_id == "clicked" {
+            // Released before calling the handler, which may take a while.
+            // Ids outlive rebuilds of the menu, a click on an entry that is
+            // gone does nothing.
+            let action = menu::get_action(&self.menu.borrow(), &self.menu_ids.borrow(), id);
+            if let Some(action) = action {
+                self.handle_action(Action::MenuItem(action));
+            }
+        }
+        Ok(())
+    }
+    fn event_group(&self, events: Vec<(i32, &str, Variant<Box<RefArg>>, u32)>) -> Result<Vec<i32>, Self::Err> {
+        for (id, event_id, data, timestamp) in events {
+            try!(self.event(id, event_id, data, timestamp));
+        }
+        Ok(Vec::new())
+    }
+    fn about_to_show(&self, id: i32) -> Result<bool, Self::Err> { Ok(false) }
+    fn about_to_show_group(&self, ids: Vec<i32>) -> Result<(Vec<i32>, Vec<i32>), Self::Err> {
+        Ok((Vec::new(), Vec::new()))
+    }
+    fn get_version(&self) -> Result<u32, Self::Err> { Ok(3) }
+    fn get_text_direction(&self) -> Result<String, Self::Err> { Ok("ltr".into()) }
+    fn get_status(&self) -> Result<String, Self::Err> { Ok("normal".into()) }
+    fn get_icon_theme_path(&self) -> Result<Vec<String>, Self::Err> { Ok(Vec::new()) }
 }
 
 impl DataType for StatusData {
@@ -66,19 +223,34 @@ impl DataType for StatusData {
 
 pub struct StatusNotifier {
     conn: Rc<Connection>,
-    status: Rc<Status>
+    status: Rc<Status>,
+    text_chan: Sender<Block>
 }
 
 impl StatusNotifier {
-    pub fn new() -> Result<StatusNotifier, dbus::Error> {
+    pub fn new(id: &str, text_chan: Sender<Block>) -> Result<StatusNotifier, dbus::Error> {
         let default_icon_name = RefCell::new(
             String::from("image-loading-symbolic"));
-        let s = Rc::new(Status{icon_name: default_icon_name});
+        let s = Rc::new(Status{
+            id: String::from(id),
+            icon_name: default_icon_name,
+            title: RefCell::new(String::new()),
+            tooltip: RefCell::new(String::new()),
+            status: RefCell::new(String::from("Active")),
+            menu: RefCell::new(Vec::new()),
+            menu_ids: RefCell::new(MenuIds::new()),
+            menu_revision: RefCell::new(0),
+            has_menu: RefCell::new(false),
+            action_handler: RefCell::new(None)
+        });
         let f = Factory::new_fn::<StatusData>();
         let iface =
             org_kde_status_notifier_item_server(&f, (), |m| m.path.get_data());
-        let t = f.tree(()).add(f.object_path(
-            "/StatusNotifierItem", s.clone()).add(iface));
+        let menu_iface =
+            com_canonical_dbusmenu_server(&f, (), |m| m.path.get_data());
+        let t = f.tree(())
+            .add(f.object_path("/StatusNotifierItem", s.clone()).add(iface))
+            .add(f.object_path("/MenuBar", s.clone()).add(menu_iface));
         match Connection::get_private(BusType::Session) {
             Ok(c) => {
                 match t.set_registered(&c, true) {
@@ -86,7 +258,8 @@ impl StatusNotifier {
                         c.add_handler(t);
                         Ok(StatusNotifier {
                             conn: Rc::new(c),
-                            status: s
+                            status: s,
+                            text_chan: text_chan
                         })
                     },
                     Err(err) => {
@@ -117,37 +290,172 @@ impl StatusNotifier {
         *self.status.icon_name.borrow_mut() = name;
     }
 
+    pub fn set_title(&mut self, title: String) {
+        *self.status.title.borrow_mut() = title;
+    }
+
+    pub fn set_action_handler(&mut self, handler: ActionHandler) {
+        *self.status.action_handler.borrow_mut() = Some(handler);
+    }
+
+    fn send_signal(&self, name: &str) {
+        if let Ok(signal) = Member::new(name) {
+            if let Ok(iface) = Interface::new("org.kde.StatusNotifierItem") {
+                let path = Path::from("/StatusNotifierItem");
+                let message = Message::signal(&path, &iface, &signal);
+                match self.conn.send(message) {
+                    Ok(_) => info!("Sent {} signal", name),
+                    Err(err) => error!("Could not send {} signal: {:?}",
+                        name, err)
+                }
+            }
+        }
+    }
+
     pub fn send_new_icon_signal(&self) {
-        let c_path = self.conn.with_path(
-            "org.freedesktop.StatusNotifierWatcher", "/StatusNotifierWatcher", 5000);
-        if let Ok(signal) = Member::new("NewIcon") {
+        self.send_signal("NewIcon");
+    }
+
+    pub fn send_new_tool_tip_signal(&self) {
+        self.send_signal("NewToolTip");
+    }
+
+    pub fn send_new_status_signal(&self) {
+        let status = self.status.status.borrow().clone();
+        if let Ok(signal) = Member::new("NewStatus") {
             if let Ok(iface) = Interface::new("org.kde.StatusNotifierItem") {
-                let message = Message::signal(&c_path.path, &iface, &signal);
+                let path = Path::from("/StatusNotifierItem");
+                let message = Message::signal(&path, &iface, &signal)
+                    .append1(status.clone());
                 match self.conn.send(message) {
-                    Ok(_) => info!("Sent new icon signal"),
-                    Err(err) => error!("Could not send new icon signal: {:?}", 
+                    Ok(_) => info!("Sent NewStatus signal: {}", status),
+                    Err(err) => error!("Could not send NewStatus signal: {:?}",
                         err)
                 }
             }
         }
     }
 
+    pub fn send_layout_updated_signal(&self) {
+        let revision = *self.status.menu_revision.borrow();
+        let path = Path::from("/MenuBar");
+        let message = Message::signal(&path, &"com.canonical.dbusmenu".into(),
+            &"LayoutUpdated".into()).append2(revision, 0i32);
+        match self.conn.send(message) {
+            Ok(_) => info!("Sent LayoutUpdated signal: {}", revision),
+            Err(err) => error!("Could not send LayoutUpdated signal: {:?}", err)
+        }
+    }
+
     pub fn update_icon(&mut self, name: String) {
         info!("Updating icon");
         self.set_icon_name(name);
         self.send_new_icon_signal();
     }
 
-    pub fn run(&mut self, in_chan: Receiver<String>) -> Result<(), dbus::Error> {
+    pub fn update(&mut self, update: StatusUpdate) {
+        if *self.status.icon_name.borrow() != update.icon_name {
+            self.update_icon(update.icon_name);
+        }
+        if *self.status.tooltip.borrow() != update.tooltip {
+            *self.status.tooltip.borrow_mut() = update.tooltip;
+            self.send_new_tool_tip_signal();
+        }
+        if *self.status.menu.borrow() != update.menu {
+            if !update.menu.is_empty() {
+                *self.status.has_menu.borrow_mut() = true;
+            }
+            self.status.menu_ids.borrow_mut().update(&update.menu);
+            *self.status.menu.borrow_mut() = update.menu;
+            *self.status.menu_revision.borrow_mut() += 1;
+            self.send_layout_updated_signal();
+        }
+        let status = if update.hidden { "Passive" } else { "Active" };
+        if *self.status.status.borrow() != status {
+            *self.status.status.borrow_mut() = String::from(status);
+            self.send_new_status_signal();
+        }
+        let block = Block {
+            id: self.status.id.clone(),
+            text: if update.hidden { String::new() } else { update.text },
+            color: update.color
+        };
+        if let Err(err) = self.text_chan.send(block) {
+            error!("Could not send status text: {:?}", err);
+        }
+    }
+
+    // Handles the updates received so far, returns false once the monitor
+    // dropped its sender
+    fn handle_updates(&mut self, updates: &Receiver<StatusUpdate>) -> bool {
+        loop {
+            match updates.try_recv() {
+                Ok(update) => self.update(update),
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => return false
+            }
+        }
+    }
+
+    pub fn run(&mut self, in_chan: Receiver<StatusUpdate>) -> Result<(), dbus::Error> {
+        // Apply the updates sent so far, so that hosts see the menu of an
+        // item that already has one when it registers
+        if !self.handle_updates(&in_chan) {
+            return Ok(())
+        }
         let reply = self.register_sni();
         match reply {
             Ok(_) => {
                 info!("StatusNotifierItem registered with watcher - starting StatusNotifier");
+                // A channel can't be polled along with the connection, so
+                // updates are forwarded by a thread that also writes to a
+                // pipe polled next to the connection's fds
+                let mut fds = [0; 2];
+                if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+                    return Err(dbus::Error::new_custom(
+                        "org.freedesktop.DBus.Error.Failed", "Could not create wakeup pipe"))
+                }
+                let mut wakeup = unsafe { File::from_raw_fd(fds[0]) };
+                let mut waker = unsafe { File::from_raw_fd(fds[1]) };
+                let (updates_tx, updates) = mpsc::channel();
+                thread::spawn(move || {
+                    for update in in_chan.iter() {
+                        if updates_tx.send(update).is_err() || waker.write_all(&[1]).is_err() {
+                            break
+                        }
+                    }
+                    // The pipe hangs up after the channel is closed, once
+                    // the monitor dropped its sender
+                    drop(updates_tx);
+                    drop(waker);
+                });
+                let mut buf = [0; 64];
                 loop {
-                    self.conn.incoming(1000).next();
-                    match in_chan.recv() {
-                        Ok(icon) => self.update_icon(icon),
-                        Err(err) => error!("Recv error: {:?}", err)
+                    let mut pollfds: Vec<libc::pollfd> = self.conn.watch_fds().iter()
+                        .map(|watch| watch.to_pollfd())
+                        .collect();
+                    pollfds.push(libc::pollfd {
+                        fd: wakeup.as_raw_fd(),
+                        events: libc::POLLIN,
+                        revents: 0
+                    });
+                    let result = unsafe {
+                        libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, -1)
+                    };
+                    if result < 0 {
+                        continue
+                    }
+                    let woken = pollfds.last().map(|pollfd| pollfd.revents).unwrap_or(0);
+                    if woken & libc::POLLIN != 0 {
+                        let _ = wakeup.read(&mut buf);
+                    }
+                    // The item goes away with the connection
+                    let running = self.handle_updates(&updates);
+                    // Answer all of the host's calls, such as Activate, and
+                    // send the signals queued by the updates
+                    for _ in self.conn.incoming(0) {}
+                    if !running {
+                        return Ok(())
                     }
                 }
             },
@@ -155,4 +463,3 @@ impl StatusNotifier {
         }
     }
 }
-
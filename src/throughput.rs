@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use config::Config;
+use status::StatusUpdate;
+
+const DEFAULT_INTERVAL: i64 = 2;
+
+// Interfaces are exposed by the kernel under this directory
+const NET_PATH: &'static str = "/sys/class/net";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Units {
+    // kB/s, MB/s...
+    Bytes,
+    // kbit/s, Mbit/s...
+    Bits
+}
+
+impl Units {
+    fn from_config(config: &Config) -> Units {
+        match config.get_str("network", "throughput_units") {
+            Some("bits") => Units::Bits,
+            Some("bytes") | None => Units::Bytes,
+            Some(units) => {
+                error!("Unknown throughput units: {}", units);
+                Units::Bytes
+            }
+        }
+    }
+}
+
+// Byte counters of an interface at some point in time
+#[derive(Clone, Debug)]
+pub struct Counters {
+    pub interface: String,
+    pub time: Instant,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64
+}
+
+fn read_counter(interface: &str, name: &str) -> Option<u64> {
+    let path = PathBuf::from(NET_PATH).join(interface).join("statistics").join(name);
+    fs::read_to_string(path).ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+pub fn read_counters(interface: &str) -> Option<Counters> {
+    match (read_counter(interface, "rx_bytes"), read_counter(interface, "tx_bytes")) {
+        (Some(rx_bytes), Some(tx_bytes)) => Some(Counters {
+            interface: interface.to_string(),
+            time: Instant::now(),
+            rx_bytes: rx_bytes,
+            tx_bytes: tx_bytes
+        }),
+        _ => None
+    }
+}
+
+// Whether the kernel reports the interface as up. Interfaces that don't
+// track their state, like some tunnels, report "unknown".
+pub fn is_interface_up(interface: &str) -> bool {
+    let path = PathBuf::from(NET_PATH).join(interface).join("operstate");
+    match fs::read_to_string(path) {
+        Ok(state) => state.trim() != "down",
+        Err(_) => false
+    }
+}
+
+fn get_seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1e9
+}
+
+fn format_rate(bytes_per_second: f64, units: Units) -> String {
+    match units {
+        Units::Bytes => format_amount(bytes_per_second, "B/s"),
+        Units::Bits => format_amount(bytes_per_second * 8.0, "bit/s")
+    }
+}
+
+// Amount with a decimal prefix, e.g. "1.2 MB"
+pub fn format_amount(value: f64, unit: &str) -> String {
+    let prefixes = ["", "k", "M", "G", "T"];
+    let mut value = value;
+    let mut index = 0;
+    while value >= 1000.0 && index < prefixes.len() - 1 {
+        value /= 1000.0;
+        index += 1;
+    }
+    if index == 0 {
+        format!("{:.0} {}{}", value, prefixes[index], unit)
+    } else {
+        format!("{:.1} {}{}", value, prefixes[index], unit)
+    }
+}
+
+// Download and upload rates of the primary interface, sampled from its sysfs
+// counters every `[network] throughput_interval` seconds while it is up
+pub struct Throughput {
+    pub interval: Duration,
+    units: Units,
+    last: RefCell<Option<Counters>>,
+    // Bytes per second received and sent over the last interval
+    rates: RefCell<Option<(f64, f64)>>
+}
+
+impl Throughput {
+    pub fn new(config: &Config) -> Option<Throughput> {
+        if !config.get_bool("network", "throughput").unwrap_or(false) {
+            return None
+        }
+        let interval = config.get_i64("network", "throughput_interval")
+            .unwrap_or(DEFAULT_INTERVAL);
+        Some(Throughput {
+            interval: Duration::from_secs(interval.max(1) as u64),
+            units: Units::from_config(config),
+            last: RefCell::new(None),
+            rates: RefCell::new(None)
+        })
+    }
+
+    // Samples the counters of `interface`, None when the interface is down.
+    // Returns whether the rates changed.
+    pub fn sample(&self, interface: Option<&str>) -> bool {
+        let counters = match interface {
+            Some(interface) if is_interface_up(interface) => read_counters(interface),
+            _ => None
+        };
+        let last = self.last.replace(counters.clone());
+        let rates = match (last, counters) {
+            // Counters go back to 0 when a device is plugged in again
+            (Some(ref last), Some(ref counters)) if last.interface == counters.interface &&
+                counters.rx_bytes >= last.rx_bytes && counters.tx_bytes >= last.tx_bytes => {
+                let seconds = get_seconds(counters.time.duration_since(last.time));
+                if seconds <= 0.0 {
+                    return false
+                }
+                Some(((counters.rx_bytes - last.rx_bytes) as f64 / seconds,
+                    (counters.tx_bytes - last.tx_bytes) as f64 / seconds))
+            },
+            // The first sample of an interface only gives the counters to
+            // start from
+            (_, Some(_)) => return false,
+            (_, None) => None
+        };
+        let changed = *self.rates.borrow() != rates;
+        *self.rates.borrow_mut() = rates;
+        changed
+    }
+
+    // The throughput item is hidden while the interface is down
+    pub fn update_status(&self) -> StatusUpdate {
+        let mut update = StatusUpdate::new(String::from("network-transmit-receive-symbolic"));
+        let (rx, tx) = match *self.rates.borrow() {
+            Some(rates) => rates,
+            None => {
+                update.hidden = true;
+                return update
+            }
+        };
+        update.text = format!("↓{} ↑{}", format_rate(rx, self.units), format_rate(tx, self.units));
+        let interface = self.last.borrow().as_ref()
+            .map(|counters| counters.interface.clone())
+            .unwrap_or(String::new());
+        update.tooltip = format!("Interface: {}\nDownload: {}\nUpload: {}",
+            interface, format_rate(rx, self.units), format_rate(tx, self.units));
+        update
+    }
+}
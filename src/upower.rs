@@ -149,6 +149,7 @@ pub trait OrgFreedesktopUPowerDevice {
     fn get_warning_level(&self) -> Result<u32, Self::Err>;
     fn get_battery_level(&self) -> Result<u32, Self::Err>;
     fn get_icon_name(&self) -> Result<String, Self::Err>;
+    fn get_charge_cycles(&self) -> Result<i32, Self::Err>;
 }
 
 impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopUPowerDevice for dbus::ConnPath<'a, C> {
@@ -300,4 +301,100 @@ impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopUPowerDevi
     fn get_icon_name(&self) -> Result<String, Self::Err> {
         <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.UPower.Device", "IconName")
     }
+
+    fn get_charge_cycles(&self) -> Result<i32, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.UPower.Device", "ChargeCycles")
+    }
+}
+
+pub trait OrgFreedesktopUPower {
+    type Err;
+    fn enumerate_devices(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err>;
+    fn get_display_device(&self) -> Result<dbus::Path<'static>, Self::Err>;
+    fn get_critical_action(&self) -> Result<String, Self::Err>;
+    fn get_daemon_version(&self) -> Result<String, Self::Err>;
+    fn get_on_battery(&self) -> Result<bool, Self::Err>;
+    fn get_lid_is_closed(&self) -> Result<bool, Self::Err>;
+    fn get_lid_is_present(&self) -> Result<bool, Self::Err>;
+}
+
+impl<'a, C: ::std::ops::Deref<Target=dbus::Connection>> OrgFreedesktopUPower for dbus::ConnPath<'a, C> {
+    type Err = dbus::Error;
+
+    fn enumerate_devices(&self) -> Result<Vec<dbus::Path<'static>>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.UPower".into(), &"EnumerateDevices".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let devices: Vec<dbus::Path<'static>> = try!(i.read());
+        Ok(devices)
+    }
+
+    fn get_display_device(&self) -> Result<dbus::Path<'static>, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.UPower".into(), &"GetDisplayDevice".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let device: dbus::Path<'static> = try!(i.read());
+        Ok(device)
+    }
+
+    fn get_critical_action(&self) -> Result<String, Self::Err> {
+        let mut m = try!(self.method_call_with_args(&"org.freedesktop.UPower".into(), &"GetCriticalAction".into(), |_| {
+        }));
+        try!(m.as_result());
+        let mut i = m.iter_init();
+        let action: String = try!(i.read());
+        Ok(action)
+    }
+
+    fn get_daemon_version(&self) -> Result<String, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.UPower", "DaemonVersion")
+    }
+
+    fn get_on_battery(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.UPower", "OnBattery")
+    }
+
+    fn get_lid_is_closed(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.UPower", "LidIsClosed")
+    }
+
+    fn get_lid_is_present(&self) -> Result<bool, Self::Err> {
+        <Self as dbus::stdintf::org_freedesktop_dbus::Properties>::get(&self, "org.freedesktop.UPower", "LidIsPresent")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopUPowerDeviceAdded {
+    pub device: dbus::Path<'static>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopUPowerDeviceAdded {
+    const NAME: &'static str = "DeviceAdded";
+    const INTERFACE: &'static str = "org.freedesktop.UPower";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.device as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.device = try!(i.read());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OrgFreedesktopUPowerDeviceRemoved {
+    pub device: dbus::Path<'static>,
+}
+
+impl dbus::SignalArgs for OrgFreedesktopUPowerDeviceRemoved {
+    const NAME: &'static str = "DeviceRemoved";
+    const INTERFACE: &'static str = "org.freedesktop.UPower";
+    fn append(&self, i: &mut arg::IterAppend) {
+        (&self.device as &arg::RefArg).append(i);
+    }
+    fn get(&mut self, i: &mut arg::Iter) -> Result<(), arg::TypeMismatchError> {
+        self.device = try!(i.read());
+        Ok(())
+    }
 }
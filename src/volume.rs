@@ -25,6 +25,7 @@ use pulse::mainloop::standard::InterateResult;
 use pulse::volume::CVolume;
 
 use VOLUME_TX;
+use status::StatusUpdate;
 
 pub struct VolumeMonitor {
     context: Context,
@@ -215,7 +216,7 @@ pub fn send_icon(icon: String) {
     info!("Sent icon: {}", icon);
     unsafe {
         let sender = VOLUME_TX.as_ref().unwrap().lock().unwrap();
-        match sender.send(icon.clone()) {
+        match sender.send(StatusUpdate::new(icon.clone())) {
             Ok(_) => info!("Sent icon: {}", icon),
             Err(err) => error!("Could not send icon: {} - {}", icon, err)
         }        
@@ -0,0 +1,50 @@
+use active_connection::{ActiveConnection, NM_ACTIVE_CONNECTION_STATE_ACTIVATING};
+use status::StatusUpdate;
+
+fn get_vpn_description(vpn: &ActiveConnection) -> String {
+    match vpn.state {
+        NM_ACTIVE_CONNECTION_STATE_ACTIVATING => format!("VPN: {} (connecting)", vpn.name),
+        _ => format!("VPN: {}", vpn.name)
+    }
+}
+
+// VPNs that are up or coming up
+fn get_vpns(connections: &[ActiveConnection]) -> Vec<&ActiveConnection> {
+    connections.iter()
+        .filter(|connection| connection.vpn)
+        .filter(|connection| connection.is_activated() ||
+            connection.state == NM_ACTIVE_CONNECTION_STATE_ACTIVATING)
+        .collect()
+}
+
+// Tooltip lines for the network item
+pub fn get_vpn_lines(connections: &[ActiveConnection]) -> Vec<String> {
+    get_vpns(connections).into_iter()
+        .map(get_vpn_description)
+        .collect()
+}
+
+// Status of the VPN item, which is hidden while no VPN is up or coming up
+pub fn get_vpn_status(connections: &[ActiveConnection]) -> StatusUpdate {
+    let vpns = get_vpns(connections);
+    if vpns.is_empty() {
+        let mut update = StatusUpdate::new(String::from("network-vpn-symbolic"));
+        update.hidden = true;
+        return update
+    }
+    let icon = if vpns.iter().all(|vpn| vpn.is_activated()) {
+        "network-vpn-symbolic"
+    } else {
+        "network-vpn-acquiring-symbolic"
+    };
+    let mut update = StatusUpdate::new(String::from(icon));
+    update.text = vpns.iter()
+        .map(|vpn| vpn.name.clone())
+        .collect::<Vec<String>>()
+        .join(", ");
+    update.tooltip = vpns.iter()
+        .map(|vpn| get_vpn_description(vpn))
+        .collect::<Vec<String>>()
+        .join("\n");
+    update
+}
@@ -0,0 +1,218 @@
+use dbus::Connection;
+use dbus::Path;
+use dbus::arg::{RefArg, Variant};
+
+use std::collections::HashMap;
+
+use menu::MenuItem;
+use network;
+use network_manager::OrgFreedesktopDBusProperties;
+use network_manager::OrgFreedesktopNetworkManager;
+use network_manager::OrgFreedesktopNetworkManagerDevice;
+use network_manager::OrgFreedesktopNetworkManagerDeviceWireless;
+use properties::PropertyCache;
+use saved_connection;
+
+// Menu actions of the network item. Connecting is followed by the device and
+// access point paths, separated by spaces.
+const ACTION_CONNECT: &'static str = "wifi-connect";
+const ACTION_SCAN: &'static str = "wifi-scan";
+
+// Settings of a connection by setting name, as taken by
+// AddAndActivateConnection
+type ConnectionSettings<'a> = HashMap<&'a str, HashMap<&'a str, Variant<Box<RefArg>>>>;
+
+// Networks listed in the menu, strongest first
+const MAX_NETWORKS: usize = 15;
+
+// NM80211ApFlags and NM80211ApSecurityFlags
+// https://developer.gnome.org/NetworkManager/stable/nm-dbus-types.html#NM80211ApFlags
+const NM_802_11_AP_FLAGS_PRIVACY: u32 = 0x1;
+const NM_802_11_AP_SEC_NONE: u32 = 0x0;
+
+// A Wi-Fi network seen by a wireless device, the strongest of its access
+// points when there are several
+#[derive(Clone, Debug, PartialEq)]
+pub struct WifiNetwork {
+    pub device: String,
+    pub access_point: String,
+    pub ssid: String,
+    pub strength: u8,
+    pub secured: bool,
+    pub active: bool
+}
+
+impl WifiNetwork {
+    fn load(conn: &Connection, device: &str, access_point: &str,
+        active_access_point: &str) -> Option<WifiNetwork> {
+        // Scans list many access points, one call each keeps reloads cheap
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", access_point, 5000);
+        let mut properties = PropertyCache::new();
+        match c_path.get_all("org.freedesktop.NetworkManager.AccessPoint") {
+            Ok(all) => {
+                properties.update(all, &[]);
+            },
+            // The access point may already be gone
+            Err(_) => return None
+        }
+        let ssid = match properties.get_bytes("Ssid") {
+            Some(ref ssid) if !ssid.is_empty() => String::from_utf8_lossy(ssid).into_owned(),
+            // Hidden networks don't broadcast their SSID
+            _ => return None
+        };
+        let flags = properties.get_u32("Flags").unwrap_or(0);
+        let wpa_flags = properties.get_u32("WpaFlags").unwrap_or(NM_802_11_AP_SEC_NONE);
+        let rsn_flags = properties.get_u32("RsnFlags").unwrap_or(NM_802_11_AP_SEC_NONE);
+        Some(WifiNetwork {
+            device: device.to_string(),
+            access_point: access_point.to_string(),
+            ssid: ssid,
+            strength: properties.get_i64("Strength").unwrap_or(0) as u8,
+            secured: flags & NM_802_11_AP_FLAGS_PRIVACY != 0 ||
+                wpa_flags != NM_802_11_AP_SEC_NONE || rsn_flags != NM_802_11_AP_SEC_NONE,
+            active: access_point == active_access_point
+        })
+    }
+
+    fn label(&self) -> String {
+        if self.secured {
+            format!("{} ({}%, secured)", self.ssid, self.strength)
+        } else {
+            format!("{} ({}%)", self.ssid, self.strength)
+        }
+    }
+}
+
+// Networks seen by the given wireless devices, one entry per SSID
+pub fn load_networks(conn: &Connection, devices: &[String]) -> Vec<WifiNetwork> {
+    let mut networks: Vec<WifiNetwork> = Vec::new();
+    for device in devices.iter() {
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", device.clone(), 5000);
+        let active_access_point = c_path.get_active_access_point()
+            .map(|access_point| access_point.to_string())
+            .unwrap_or(String::new());
+        let access_points = match c_path.get_access_points() {
+            Ok(access_points) => access_points,
+            Err(err) => {
+                error!("Could not get access points of {}: {:?}", device, err);
+                continue
+            }
+        };
+        for access_point in access_points.iter() {
+            let network = match WifiNetwork::load(conn, device, access_point, &active_access_point) {
+                Some(network) => network,
+                None => continue
+            };
+            match networks.iter().position(|other| other.ssid == network.ssid) {
+                Some(index) => {
+                    let active = networks[index].active || network.active;
+                    if network.strength > networks[index].strength {
+                        networks[index] = network;
+                    }
+                    networks[index].active = active;
+                },
+                None => networks.push(network)
+            }
+        }
+    }
+    networks.sort_by(|a, b| b.active.cmp(&a.active).then(b.strength.cmp(&a.strength)));
+    networks.truncate(MAX_NETWORKS);
+    networks
+}
+
+// "Wi-Fi networks" submenu, None without a wireless device
+pub fn menu_item(networks: &[WifiNetwork], has_wireless: bool) -> Option<MenuItem> {
+    if !has_wireless {
+        return None
+    }
+    let mut items: Vec<MenuItem> = networks.iter().map(|network| {
+        let mut item = MenuItem::new(network.label());
+        // The active network is only shown checked
+        if network.active {
+            item.checked = Some(true);
+        } else {
+            item.action = Some(format!("{} {} {}",
+                ACTION_CONNECT, network.device, network.access_point));
+        }
+        item
+    }).collect();
+    if items.is_empty() {
+        items.push(MenuItem::new(String::from("No networks found")));
+    }
+    items.push(MenuItem::separator());
+    items.push(MenuItem::with_action(String::from("Scan for networks"), String::from(ACTION_SCAN)));
+    Some(MenuItem::submenu(String::from("Wi-Fi networks"), items))
+}
+
+fn request_scan(conn: &Connection, devices: &[Path<'static>]) {
+    for device in devices.iter() {
+        let c_path = conn.with_path("org.freedesktop.NetworkManager", device.clone(), 5000);
+        match c_path.get_device_type() {
+            Ok(network::NM_DEVICE_TYPE_WIFI) => {},
+            _ => continue
+        }
+        info!("Scanning for Wi-Fi networks on {}", device);
+        if let Err(err) = c_path.request_scan(HashMap::new()) {
+            error!("Could not scan for Wi-Fi networks: {:?}", err);
+        }
+    }
+}
+
+// Activates a saved profile for the network, NetworkManager picks it when no
+// connection is given. Open networks without a profile get a new one, secured
+// ones are left to the user's NetworkManager agent to set up.
+fn connect(conn: &Connection, device: &str, access_point: &str) {
+    let (device, access_point) = match (Path::new(device), Path::new(access_point)) {
+        (Ok(device), Ok(access_point)) => (device, access_point),
+        _ => {
+            error!("Invalid Wi-Fi network: {} {}", device, access_point);
+            return
+        }
+    };
+    let network = match WifiNetwork::load(conn, &device, &access_point, "") {
+        Some(network) => network,
+        None => {
+            error!("Wi-Fi network {} is gone", access_point);
+            return
+        }
+    };
+    let c_path = conn.with_path(
+        "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 30000);
+    info!("Connecting to Wi-Fi network {}", network.ssid);
+    let err = match c_path.activate_connection(
+        Path::from("/"), device.clone(), access_point.clone()) {
+        Ok(_) => return,
+        Err(err) => err
+    };
+    if network.secured {
+        error!("Could not connect to {}, no saved connection: {:?}", network.ssid, err);
+        return
+    }
+    info!("Adding a connection for open Wi-Fi network {}", network.ssid);
+    let settings: ConnectionSettings = HashMap::new();
+    if let Err(err) = c_path.add_and_activate_connection(settings, device, access_point) {
+        error!("Could not connect to {}: {:?}", network.ssid, err);
+    }
+}
+
+// Runs a Wi-Fi menu action, returns false for other actions
+pub fn run_action(conn: &Connection, action: &str) -> bool {
+    let words: Vec<&str> = action.split(' ').collect();
+    match words.as_slice() {
+        [ACTION_CONNECT, device, access_point] => {
+            let device = device.to_string();
+            let access_point = access_point.to_string();
+            saved_connection::spawn(move |conn| connect(conn, &device, &access_point))
+        },
+        [ACTION_SCAN] => {
+            let c_path = conn.with_path(
+                "org.freedesktop.NetworkManager", "/org/freedesktop/NetworkManager", 5000);
+            match OrgFreedesktopNetworkManager::get_devices(&c_path) {
+                Ok(devices) => request_scan(conn, &devices),
+                Err(err) => error!("Could not get network devices: {:?}", err)
+            }
+        },
+        _ => return false
+    }
+    true
+}